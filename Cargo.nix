@@ -17,12 +17,13 @@ rec {
         (cratesIO.crates."aster"."${deps."shen_rust"."0.0.1"."aster"}" deps)
         (cratesIO.crates."libc"."${deps."shen_rust"."0.0.1"."libc"}" deps)
         (cratesIO.crates."nom"."${deps."shen_rust"."0.0.1"."nom"}" deps)
+        (cratesIO.crates."num_bigint"."${deps."shen_rust"."0.0.1"."num_bigint"}" deps)
+        (cratesIO.crates."num_traits"."${deps."shen_rust"."0.0.1"."num_traits"}" deps)
         (cratesIO.crates."quasi"."${deps."shen_rust"."0.0.1"."quasi"}" deps)
         (cratesIO.crates."ref_eq"."${deps."shen_rust"."0.0.1"."ref_eq"}" deps)
         (cratesIO.crates."syntex"."${deps."shen_rust"."0.0.1"."syntex"}" deps)
         (cratesIO.crates."syntex_syntax"."${deps."shen_rust"."0.0.1"."syntex_syntax"}" deps)
         (cratesIO.crates."time"."${deps."shen_rust"."0.0.1"."time"}" deps)
-        (cratesIO.crates."uuid"."${deps."shen_rust"."0.0.1"."uuid"}" deps)
       ]);
     };
     features_.shen_rust."0.0.1" = deps: f: updateFeatures f (rec {
@@ -32,6 +33,8 @@ rec {
       ];
       libc."${deps.shen_rust."0.0.1".libc}".default = true;
       nom."${deps.shen_rust."0.0.1".nom}".default = true;
+      num_bigint."${deps.shen_rust."0.0.1".num_bigint}".default = true;
+      num_traits."${deps.shen_rust."0.0.1".num_traits}".default = true;
       quasi = fold recursiveUpdate {} [
         { "${deps.shen_rust."0.0.1".quasi}"."with-syntex" = true; }
         { "${deps.shen_rust."0.0.1".quasi}".default = true; }
@@ -41,20 +44,17 @@ rec {
       syntex."${deps.shen_rust."0.0.1".syntex}".default = true;
       syntex_syntax."${deps.shen_rust."0.0.1".syntex_syntax}".default = true;
       time."${deps.shen_rust."0.0.1".time}".default = true;
-      uuid = fold recursiveUpdate {} [
-        { "${deps.shen_rust."0.0.1".uuid}"."v4" = true; }
-        { "${deps.shen_rust."0.0.1".uuid}".default = true; }
-      ];
     }) [
       (cratesIO.features_.aster."${deps."shen_rust"."0.0.1"."aster"}" deps)
       (cratesIO.features_.libc."${deps."shen_rust"."0.0.1"."libc"}" deps)
       (cratesIO.features_.nom."${deps."shen_rust"."0.0.1"."nom"}" deps)
+      (cratesIO.features_.num_bigint."${deps."shen_rust"."0.0.1"."num_bigint"}" deps)
+      (cratesIO.features_.num_traits."${deps."shen_rust"."0.0.1"."num_traits"}" deps)
       (cratesIO.features_.quasi."${deps."shen_rust"."0.0.1"."quasi"}" deps)
       (cratesIO.features_.ref_eq."${deps."shen_rust"."0.0.1"."ref_eq"}" deps)
       (cratesIO.features_.syntex."${deps."shen_rust"."0.0.1"."syntex"}" deps)
       (cratesIO.features_.syntex_syntax."${deps."shen_rust"."0.0.1"."syntex_syntax"}" deps)
       (cratesIO.features_.time."${deps."shen_rust"."0.0.1"."time"}" deps)
-      (cratesIO.features_.uuid."${deps."shen_rust"."0.0.1"."uuid"}" deps)
     ];
 
 
@@ -67,9 +67,9 @@ rec {
   deps.aster."0.25.0" = {
     syntex_syntax = "0.42.0";
   };
+  deps.autocfg."1.5.1" = {};
   deps.bitflags."0.5.0" = {};
   deps.cfg_if."0.1.9" = {};
-  deps.fuchsia_cprng."0.1.1" = {};
   deps.kernel32_sys."0.2.2" = {
     winapi = "0.2.8";
     winapi_build = "0.1.1";
@@ -82,27 +82,20 @@ rec {
     cfg_if = "0.1.9";
   };
   deps.nom."1.2.4" = {};
-  deps.quasi."0.18.0" = {
-    syntex_errors = "0.42.0";
-    syntex_syntax = "0.42.0";
-  };
-  deps.rand."0.3.23" = {
-    libc = "0.2.60";
-    rand = "0.4.6";
+  deps.num_bigint."0.2.6" = {
+    num_integer = "0.1.47";
+    num_traits = "0.2.19";
+    autocfg = "1.5.1";
   };
-  deps.rand."0.4.6" = {
-    rand_core = "0.3.1";
-    rdrand = "0.4.0";
-    fuchsia_cprng = "0.1.1";
-    libc = "0.2.60";
-    winapi = "0.3.7";
+  deps.num_integer."0.1.47" = {
+    num_traits = "0.2.19";
   };
-  deps.rand_core."0.3.1" = {
-    rand_core = "0.4.0";
+  deps.num_traits."0.2.19" = {
+    autocfg = "1.5.1";
   };
-  deps.rand_core."0.4.0" = {};
-  deps.rdrand."0.4.0" = {
-    rand_core = "0.3.1";
+  deps.quasi."0.18.0" = {
+    syntex_errors = "0.42.0";
+    syntex_syntax = "0.42.0";
   };
   deps.redox_syscall."0.1.56" = {};
   deps.ref_eq."1.0.0" = {};
@@ -111,12 +104,13 @@ rec {
     aster = "0.25.0";
     libc = "0.2.60";
     nom = "1.2.4";
+    num_bigint = "0.2.6";
+    num_traits = "0.2.19";
     quasi = "0.18.0";
     ref_eq = "1.0.0";
     syntex = "0.42.2";
     syntex_syntax = "0.42.0";
     time = "0.1.42";
-    uuid = "0.2.3";
   };
   deps.syntex."0.42.2" = {
     syntex_errors = "0.42.0";
@@ -153,9 +147,6 @@ rec {
     winapi = "0.3.7";
   };
   deps.unicode_xid."0.0.3" = {};
-  deps.uuid."0.2.3" = {
-    rand = "0.3.23";
-  };
   deps.winapi."0.2.8" = {};
   deps.winapi."0.3.7" = {
     winapi_i686_pc_windows_gnu = "0.4.0";
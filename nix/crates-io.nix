@@ -39,6 +39,20 @@ rec {
   ];
 
 
+# end
+# autocfg-1.5.1
+
+  crates.autocfg."1.5.1" = deps: { features?(features_.autocfg."1.5.1" deps {}) }: buildRustCrate {
+    crateName = "autocfg";
+    version = "1.5.1";
+    authors = [ "Josh Stone <cuviper@gmail.com>" ];
+    sha256 = "1dfqr9dji0vsvg7lwya2768071jr8hbid2jxa0wvwzmx2i86m4bx";
+  };
+  features_.autocfg."1.5.1" = deps: f: updateFeatures f (rec {
+    autocfg."1.5.1".default = (f.autocfg."1.5.1".default or true);
+  }) [];
+
+
 # end
 # bitflags-0.5.0
 
@@ -68,21 +82,6 @@ rec {
   }) [];
 
 
-# end
-# fuchsia-cprng-0.1.1
-
-  crates.fuchsia_cprng."0.1.1" = deps: { features?(features_.fuchsia_cprng."0.1.1" deps {}) }: buildRustCrate {
-    crateName = "fuchsia-cprng";
-    version = "0.1.1";
-    authors = [ "Erick Tryzelaar <etryzelaar@google.com>" ];
-    edition = "2018";
-    sha256 = "07apwv9dj716yjlcj29p94vkqn5zmfh7hlrqvrjx3wzshphc95h9";
-  };
-  features_.fuchsia_cprng."0.1.1" = deps: f: updateFeatures f (rec {
-    fuchsia_cprng."0.1.1".default = (f.fuchsia_cprng."0.1.1".default or true);
-  }) [];
-
-
 # end
 # kernel32-sys-0.2.2
 
@@ -282,226 +281,164 @@ rec {
 
 
 # end
-# quasi-0.18.0
+# num-bigint-0.2.6
 
-  crates.quasi."0.18.0" = deps: { features?(features_.quasi."0.18.0" deps {}) }: buildRustCrate {
-    crateName = "quasi";
-    version = "0.18.0";
-    authors = [ "Erick Tryzelaar <erick.tryzelaar@gmail.com>" ];
-    sha256 = "02lzkpbak6cixk6zp0a96wi8p2m82q25s55k4vcfy38f58ap8gb8";
-    dependencies = mapFeatures features ([
-    ]
-      ++ (if features.quasi."0.18.0".syntex_errors or false then [ (crates.syntex_errors."${deps."quasi"."0.18.0".syntex_errors}" deps) ] else [])
-      ++ (if features.quasi."0.18.0".syntex_syntax or false then [ (crates.syntex_syntax."${deps."quasi"."0.18.0".syntex_syntax}" deps) ] else []));
-    features = mkFeatures (features."quasi"."0.18.0" or {});
-  };
-  features_.quasi."0.18.0" = deps: f: updateFeatures f (rec {
-    quasi = fold recursiveUpdate {} [
-      { "0.18.0".clippy =
-        (f.quasi."0.18.0".clippy or false) ||
-        (f.quasi."0.18.0".unstable-testing or false) ||
-        (quasi."0.18.0"."unstable-testing" or false); }
-      { "0.18.0".default = (f.quasi."0.18.0".default or true); }
-      { "0.18.0".syntex_errors =
-        (f.quasi."0.18.0".syntex_errors or false) ||
-        (f.quasi."0.18.0".with-syntex or false) ||
-        (quasi."0.18.0"."with-syntex" or false); }
-      { "0.18.0".syntex_syntax =
-        (f.quasi."0.18.0".syntex_syntax or false) ||
-        (f.quasi."0.18.0".with-syntex or false) ||
-        (quasi."0.18.0"."with-syntex" or false); }
-    ];
-    syntex_errors."${deps.quasi."0.18.0".syntex_errors}".default = true;
-    syntex_syntax."${deps.quasi."0.18.0".syntex_syntax}".default = true;
-  }) [
-    (features_.syntex_errors."${deps."quasi"."0.18.0"."syntex_errors"}" deps)
-    (features_.syntex_syntax."${deps."quasi"."0.18.0"."syntex_syntax"}" deps)
-  ];
-
-
-# end
-# rand-0.3.23
-
-  crates.rand."0.3.23" = deps: { features?(features_.rand."0.3.23" deps {}) }: buildRustCrate {
-    crateName = "rand";
-    version = "0.3.23";
+  crates.num_bigint."0.2.6" = deps: { features?(features_.num_bigint."0.2.6" deps {}) }: buildRustCrate {
+    crateName = "num-bigint";
+    version = "0.2.6";
     authors = [ "The Rust Project Developers" ];
-    sha256 = "118rairvv46npqqx7hmkf97kkimjrry9z31z4inxcv2vn0nj1s2g";
+    sha256 = "005q85mcsn6qcakddylbpyx47kp4cb3hmsq0250ywbapr5kyl341";
+    build = "build.rs";
     dependencies = mapFeatures features ([
-      (crates."libc"."${deps."rand"."0.3.23"."libc"}" deps)
-      (crates."rand"."${deps."rand"."0.3.23"."rand"}" deps)
+      (crates."num_integer"."${deps."num_bigint"."0.2.6"."num_integer"}" deps)
+      (crates."num_traits"."${deps."num_bigint"."0.2.6"."num_traits"}" deps)
     ]);
-    features = mkFeatures (features."rand"."0.3.23" or {});
-  };
-  features_.rand."0.3.23" = deps: f: updateFeatures f (rec {
-    libc."${deps.rand."0.3.23".libc}".default = true;
-    rand = fold recursiveUpdate {} [
-      { "${deps.rand."0.3.23".rand}".default = true; }
-      { "0.3.23".default = (f.rand."0.3.23".default or true); }
-      { "0.3.23".i128_support =
-        (f.rand."0.3.23".i128_support or false) ||
-        (f.rand."0.3.23".nightly or false) ||
-        (rand."0.3.23"."nightly" or false); }
-    ];
-  }) [
-    (features_.libc."${deps."rand"."0.3.23"."libc"}" deps)
-    (features_.rand."${deps."rand"."0.3.23"."rand"}" deps)
-  ];
-
-
-# end
-# rand-0.4.6
 
-  crates.rand."0.4.6" = deps: { features?(features_.rand."0.4.6" deps {}) }: buildRustCrate {
-    crateName = "rand";
-    version = "0.4.6";
-    authors = [ "The Rust Project Developers" ];
-    sha256 = "0c3rmg5q7d6qdi7cbmg5py9alm70wd3xsg0mmcawrnl35qv37zfs";
-    dependencies = (if abi == "sgx" then mapFeatures features ([
-      (crates."rand_core"."${deps."rand"."0.4.6"."rand_core"}" deps)
-      (crates."rdrand"."${deps."rand"."0.4.6"."rdrand"}" deps)
-    ]) else [])
-      ++ (if kernel == "fuchsia" then mapFeatures features ([
-      (crates."fuchsia_cprng"."${deps."rand"."0.4.6"."fuchsia_cprng"}" deps)
-    ]) else [])
-      ++ (if (kernel == "linux" || kernel == "darwin") then mapFeatures features ([
-    ]
-      ++ (if features.rand."0.4.6".libc or false then [ (crates.libc."${deps."rand"."0.4.6".libc}" deps) ] else [])) else [])
-      ++ (if kernel == "windows" then mapFeatures features ([
-      (crates."winapi"."${deps."rand"."0.4.6"."winapi"}" deps)
-    ]) else []);
-    features = mkFeatures (features."rand"."0.4.6" or {});
+    buildDependencies = mapFeatures features ([
+      (crates."autocfg"."${deps."num_bigint"."0.2.6"."autocfg"}" deps)
+    ]);
+    features = mkFeatures (features."num_bigint"."0.2.6" or {});
   };
-  features_.rand."0.4.6" = deps: f: updateFeatures f (rec {
-    fuchsia_cprng."${deps.rand."0.4.6".fuchsia_cprng}".default = true;
-    libc."${deps.rand."0.4.6".libc}".default = true;
-    rand = fold recursiveUpdate {} [
-      { "0.4.6".default = (f.rand."0.4.6".default or true); }
-      { "0.4.6".i128_support =
-        (f.rand."0.4.6".i128_support or false) ||
-        (f.rand."0.4.6".nightly or false) ||
-        (rand."0.4.6"."nightly" or false); }
-      { "0.4.6".libc =
-        (f.rand."0.4.6".libc or false) ||
-        (f.rand."0.4.6".std or false) ||
-        (rand."0.4.6"."std" or false); }
-      { "0.4.6".std =
-        (f.rand."0.4.6".std or false) ||
-        (f.rand."0.4.6".default or false) ||
-        (rand."0.4.6"."default" or false); }
+  features_.num_bigint."0.2.6" = deps: f: updateFeatures f (rec {
+    autocfg."${deps.num_bigint."0.2.6".autocfg}".default = true;
+    num_bigint = fold recursiveUpdate {} [
+      { "0.2.6".default = (f.num_bigint."0.2.6".default or true); }
+      { "0.2.6".std =
+        (f.num_bigint."0.2.6".std or false) ||
+        (f.num_bigint."0.2.6".default or false) ||
+        (num_bigint."0.2.6"."default" or false); }
     ];
-    rand_core."${deps.rand."0.4.6".rand_core}".default = (f.rand_core."${deps.rand."0.4.6".rand_core}".default or false);
-    rdrand."${deps.rand."0.4.6".rdrand}".default = true;
-    winapi = fold recursiveUpdate {} [
-      { "${deps.rand."0.4.6".winapi}"."minwindef" = true; }
-      { "${deps.rand."0.4.6".winapi}"."ntsecapi" = true; }
-      { "${deps.rand."0.4.6".winapi}"."profileapi" = true; }
-      { "${deps.rand."0.4.6".winapi}"."winnt" = true; }
-      { "${deps.rand."0.4.6".winapi}".default = true; }
+    num_integer = fold recursiveUpdate {} [
+      { "${deps.num_bigint."0.2.6".num_integer}"."i128" =
+        (f.num_integer."${deps.num_bigint."0.2.6".num_integer}"."i128" or false) ||
+        (num_bigint."0.2.6"."i128" or false) ||
+        (f."num_bigint"."0.2.6"."i128" or false); }
+      { "${deps.num_bigint."0.2.6".num_integer}"."std" =
+        (f.num_integer."${deps.num_bigint."0.2.6".num_integer}"."std" or false) ||
+        (num_bigint."0.2.6"."std" or false) ||
+        (f."num_bigint"."0.2.6"."std" or false); }
+      { "${deps.num_bigint."0.2.6".num_integer}".default = (f.num_integer."${deps.num_bigint."0.2.6".num_integer}".default or false); }
+    ];
+    num_traits = fold recursiveUpdate {} [
+      { "${deps.num_bigint."0.2.6".num_traits}"."i128" =
+        (f.num_traits."${deps.num_bigint."0.2.6".num_traits}"."i128" or false) ||
+        (num_bigint."0.2.6"."i128" or false) ||
+        (f."num_bigint"."0.2.6"."i128" or false); }
+      { "${deps.num_bigint."0.2.6".num_traits}"."std" =
+        (f.num_traits."${deps.num_bigint."0.2.6".num_traits}"."std" or false) ||
+        (num_bigint."0.2.6"."std" or false) ||
+        (f."num_bigint"."0.2.6"."std" or false); }
+      { "${deps.num_bigint."0.2.6".num_traits}".default = (f.num_traits."${deps.num_bigint."0.2.6".num_traits}".default or false); }
     ];
   }) [
-    (features_.rand_core."${deps."rand"."0.4.6"."rand_core"}" deps)
-    (features_.rdrand."${deps."rand"."0.4.6"."rdrand"}" deps)
-    (features_.fuchsia_cprng."${deps."rand"."0.4.6"."fuchsia_cprng"}" deps)
-    (features_.libc."${deps."rand"."0.4.6"."libc"}" deps)
-    (features_.winapi."${deps."rand"."0.4.6"."winapi"}" deps)
+    (features_.num_integer."${deps."num_bigint"."0.2.6"."num_integer"}" deps)
+    (features_.num_traits."${deps."num_bigint"."0.2.6"."num_traits"}" deps)
+    (features_.autocfg."${deps."num_bigint"."0.2.6"."autocfg"}" deps)
   ];
 
 
 # end
-# rand_core-0.3.1
+# num-integer-0.1.47
 
-  crates.rand_core."0.3.1" = deps: { features?(features_.rand_core."0.3.1" deps {}) }: buildRustCrate {
-    crateName = "rand_core";
-    version = "0.3.1";
-    authors = [ "The Rand Project Developers" "The Rust Project Developers" ];
-    sha256 = "0q0ssgpj9x5a6fda83nhmfydy7a6c0wvxm0jhncsmjx8qp8gw91m";
+  crates.num_integer."0.1.47" = deps: { features?(features_.num_integer."0.1.47" deps {}) }: buildRustCrate {
+    crateName = "num-integer";
+    version = "0.1.47";
+    authors = [ "The Rust Project Developers" ];
+    edition = "2018";
+    sha256 = "1dpwzqzyqpwrv1wgrhfyf0jsh4l6wwc0pw7y19yp7y2x332rp3if";
     dependencies = mapFeatures features ([
-      (crates."rand_core"."${deps."rand_core"."0.3.1"."rand_core"}" deps)
+      (crates."num_traits"."${deps."num_integer"."0.1.47"."num_traits"}" deps)
     ]);
-    features = mkFeatures (features."rand_core"."0.3.1" or {});
+    features = mkFeatures (features."num_integer"."0.1.47" or {});
   };
-  features_.rand_core."0.3.1" = deps: f: updateFeatures f (rec {
-    rand_core = fold recursiveUpdate {} [
-      { "${deps.rand_core."0.3.1".rand_core}"."alloc" =
-        (f.rand_core."${deps.rand_core."0.3.1".rand_core}"."alloc" or false) ||
-        (rand_core."0.3.1"."alloc" or false) ||
-        (f."rand_core"."0.3.1"."alloc" or false); }
-      { "${deps.rand_core."0.3.1".rand_core}"."serde1" =
-        (f.rand_core."${deps.rand_core."0.3.1".rand_core}"."serde1" or false) ||
-        (rand_core."0.3.1"."serde1" or false) ||
-        (f."rand_core"."0.3.1"."serde1" or false); }
-      { "${deps.rand_core."0.3.1".rand_core}"."std" =
-        (f.rand_core."${deps.rand_core."0.3.1".rand_core}"."std" or false) ||
-        (rand_core."0.3.1"."std" or false) ||
-        (f."rand_core"."0.3.1"."std" or false); }
-      { "${deps.rand_core."0.3.1".rand_core}".default = true; }
-      { "0.3.1".default = (f.rand_core."0.3.1".default or true); }
-      { "0.3.1".std =
-        (f.rand_core."0.3.1".std or false) ||
-        (f.rand_core."0.3.1".default or false) ||
-        (rand_core."0.3.1"."default" or false); }
+  features_.num_integer."0.1.47" = deps: f: updateFeatures f (rec {
+    num_integer = fold recursiveUpdate {} [
+      { "0.1.47".default = (f.num_integer."0.1.47".default or true); }
+      { "0.1.47".std =
+        (f.num_integer."0.1.47".std or false) ||
+        (f.num_integer."0.1.47".default or false) ||
+        (num_integer."0.1.47"."default" or false); }
+    ];
+    num_traits = fold recursiveUpdate {} [
+      { "${deps.num_integer."0.1.47".num_traits}"."i128" = true; }
+      { "${deps.num_integer."0.1.47".num_traits}"."std" =
+        (f.num_traits."${deps.num_integer."0.1.47".num_traits}"."std" or false) ||
+        (num_integer."0.1.47"."std" or false) ||
+        (f."num_integer"."0.1.47"."std" or false); }
+      { "${deps.num_integer."0.1.47".num_traits}".default = (f.num_traits."${deps.num_integer."0.1.47".num_traits}".default or false); }
     ];
   }) [
-    (features_.rand_core."${deps."rand_core"."0.3.1"."rand_core"}" deps)
+    (features_.num_traits."${deps."num_integer"."0.1.47"."num_traits"}" deps)
   ];
 
 
 # end
-# rand_core-0.4.0
+# num-traits-0.2.19
 
-  crates.rand_core."0.4.0" = deps: { features?(features_.rand_core."0.4.0" deps {}) }: buildRustCrate {
-    crateName = "rand_core";
-    version = "0.4.0";
-    authors = [ "The Rand Project Developers" "The Rust Project Developers" ];
-    sha256 = "0wb5iwhffibj0pnpznhv1g3i7h1fnhz64s3nz74fz6vsm3q6q3br";
+  crates.num_traits."0.2.19" = deps: { features?(features_.num_traits."0.2.19" deps {}) }: buildRustCrate {
+    crateName = "num-traits";
+    version = "0.2.19";
+    authors = [ "The Rust Project Developers" ];
+    edition = "2021";
+    sha256 = "0j8mr5cwz62brjbab87r1ji25lfz8vvkswaindqqvwm60wl2dgg4";
+    build = "build.rs";
     dependencies = mapFeatures features ([
 ]);
-    features = mkFeatures (features."rand_core"."0.4.0" or {});
+
+    buildDependencies = mapFeatures features ([
+      (crates."autocfg"."${deps."num_traits"."0.2.19"."autocfg"}" deps)
+    ]);
+    features = mkFeatures (features."num_traits"."0.2.19" or {});
   };
-  features_.rand_core."0.4.0" = deps: f: updateFeatures f (rec {
-    rand_core = fold recursiveUpdate {} [
-      { "0.4.0".alloc =
-        (f.rand_core."0.4.0".alloc or false) ||
-        (f.rand_core."0.4.0".std or false) ||
-        (rand_core."0.4.0"."std" or false); }
-      { "0.4.0".default = (f.rand_core."0.4.0".default or true); }
-      { "0.4.0".serde =
-        (f.rand_core."0.4.0".serde or false) ||
-        (f.rand_core."0.4.0".serde1 or false) ||
-        (rand_core."0.4.0"."serde1" or false); }
-      { "0.4.0".serde_derive =
-        (f.rand_core."0.4.0".serde_derive or false) ||
-        (f.rand_core."0.4.0".serde1 or false) ||
-        (rand_core."0.4.0"."serde1" or false); }
+  features_.num_traits."0.2.19" = deps: f: updateFeatures f (rec {
+    autocfg."${deps.num_traits."0.2.19".autocfg}".default = true;
+    num_traits = fold recursiveUpdate {} [
+      { "0.2.19".default = (f.num_traits."0.2.19".default or true); }
+      { "0.2.19".std =
+        (f.num_traits."0.2.19".std or false) ||
+        (f.num_traits."0.2.19".default or false) ||
+        (num_traits."0.2.19"."default" or false); }
     ];
-  }) [];
+  }) [
+    (features_.autocfg."${deps."num_traits"."0.2.19"."autocfg"}" deps)
+  ];
 
 
 # end
-# rdrand-0.4.0
+# quasi-0.18.0
 
-  crates.rdrand."0.4.0" = deps: { features?(features_.rdrand."0.4.0" deps {}) }: buildRustCrate {
-    crateName = "rdrand";
-    version = "0.4.0";
-    authors = [ "Simonas Kazlauskas <rdrand@kazlauskas.me>" ];
-    sha256 = "15hrcasn0v876wpkwab1dwbk9kvqwrb3iv4y4dibb6yxnfvzwajk";
+  crates.quasi."0.18.0" = deps: { features?(features_.quasi."0.18.0" deps {}) }: buildRustCrate {
+    crateName = "quasi";
+    version = "0.18.0";
+    authors = [ "Erick Tryzelaar <erick.tryzelaar@gmail.com>" ];
+    sha256 = "02lzkpbak6cixk6zp0a96wi8p2m82q25s55k4vcfy38f58ap8gb8";
     dependencies = mapFeatures features ([
-      (crates."rand_core"."${deps."rdrand"."0.4.0"."rand_core"}" deps)
-    ]);
-    features = mkFeatures (features."rdrand"."0.4.0" or {});
+    ]
+      ++ (if features.quasi."0.18.0".syntex_errors or false then [ (crates.syntex_errors."${deps."quasi"."0.18.0".syntex_errors}" deps) ] else [])
+      ++ (if features.quasi."0.18.0".syntex_syntax or false then [ (crates.syntex_syntax."${deps."quasi"."0.18.0".syntex_syntax}" deps) ] else []));
+    features = mkFeatures (features."quasi"."0.18.0" or {});
   };
-  features_.rdrand."0.4.0" = deps: f: updateFeatures f (rec {
-    rand_core."${deps.rdrand."0.4.0".rand_core}".default = (f.rand_core."${deps.rdrand."0.4.0".rand_core}".default or false);
-    rdrand = fold recursiveUpdate {} [
-      { "0.4.0".default = (f.rdrand."0.4.0".default or true); }
-      { "0.4.0".std =
-        (f.rdrand."0.4.0".std or false) ||
-        (f.rdrand."0.4.0".default or false) ||
-        (rdrand."0.4.0"."default" or false); }
+  features_.quasi."0.18.0" = deps: f: updateFeatures f (rec {
+    quasi = fold recursiveUpdate {} [
+      { "0.18.0".clippy =
+        (f.quasi."0.18.0".clippy or false) ||
+        (f.quasi."0.18.0".unstable-testing or false) ||
+        (quasi."0.18.0"."unstable-testing" or false); }
+      { "0.18.0".default = (f.quasi."0.18.0".default or true); }
+      { "0.18.0".syntex_errors =
+        (f.quasi."0.18.0".syntex_errors or false) ||
+        (f.quasi."0.18.0".with-syntex or false) ||
+        (quasi."0.18.0"."with-syntex" or false); }
+      { "0.18.0".syntex_syntax =
+        (f.quasi."0.18.0".syntex_syntax or false) ||
+        (f.quasi."0.18.0".with-syntex or false) ||
+        (quasi."0.18.0"."with-syntex" or false); }
     ];
+    syntex_errors."${deps.quasi."0.18.0".syntex_errors}".default = true;
+    syntex_syntax."${deps.quasi."0.18.0".syntex_syntax}".default = true;
   }) [
-    (features_.rand_core."${deps."rdrand"."0.4.0"."rand_core"}" deps)
+    (features_.syntex_errors."${deps."quasi"."0.18.0"."syntex_errors"}" deps)
+    (features_.syntex_syntax."${deps."quasi"."0.18.0"."syntex_syntax"}" deps)
   ];
 
 
@@ -744,37 +681,6 @@ rec {
   }) [];
 
 
-# end
-# uuid-0.2.3
-
-  crates.uuid."0.2.3" = deps: { features?(features_.uuid."0.2.3" deps {}) }: buildRustCrate {
-    crateName = "uuid";
-    version = "0.2.3";
-    authors = [ "The Rust Project Developers" ];
-    sha256 = "0xw4cy74rwkfqrzbqf85349q36iz20fagdnj8d82h0rdjlmb9na3";
-    dependencies = mapFeatures features ([
-    ]
-      ++ (if features.uuid."0.2.3".rand or false then [ (crates.rand."${deps."uuid"."0.2.3".rand}" deps) ] else []));
-    features = mkFeatures (features."uuid"."0.2.3" or {});
-  };
-  features_.uuid."0.2.3" = deps: f: updateFeatures f (rec {
-    rand."${deps.uuid."0.2.3".rand}".default = true;
-    uuid = fold recursiveUpdate {} [
-      { "0.2.3".default = (f.uuid."0.2.3".default or true); }
-      { "0.2.3".rand =
-        (f.uuid."0.2.3".rand or false) ||
-        (f.uuid."0.2.3".v4 or false) ||
-        (uuid."0.2.3"."v4" or false); }
-      { "0.2.3".sha1 =
-        (f.uuid."0.2.3".sha1 or false) ||
-        (f.uuid."0.2.3".v5 or false) ||
-        (uuid."0.2.3"."v5" or false); }
-    ];
-  }) [
-    (features_.rand."${deps."uuid"."0.2.3"."rand"}" deps)
-  ];
-
-
 # end
 # winapi-0.2.8
 
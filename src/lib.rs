@@ -0,0 +1,5340 @@
+// [[file:../shen-rust.org::*Preamble][Preamble:1]]
+
+#[macro_use]
+extern crate nom;
+extern crate time;
+extern crate core;
+extern crate num_bigint;
+extern crate num_traits;
+use std::str;
+use nom::*;
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::prelude::*;
+use std::rc::Rc;
+use std::collections::{HashMap, HashSet};
+use std::cell::{Cell, RefCell};
+use std::io::{self, Error};
+use std::convert::TryFrom;
+use std::ops::{Add, Sub, Mul};
+use std::fmt;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::thread;
+use num_bigint::{BigInt, Sign};
+use num_traits::{FromPrimitive, ToPrimitive, Zero, pow};
+// Preamble:1 ends here
+
+// [[file:../shen-rust.org::*Token%20Types][Token\ Types:1]]
+#[derive(Debug, Clone, PartialEq)]
+pub enum KlToken {
+    Symbol(KlSymbol),
+    Number(KlNumber),
+    String(String),
+    Cons(Vec<KlToken>),
+    Recur(Vec<KlToken>)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KlSymbol(u32, PhantomData<Rc<()>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KlNumber {
+    Float(f64),
+    Int(i64),
+    Big(BigInt)
+}
+
+// A vector's identity is its Rc: two absvectors are the same vector only if
+// they point at the same UniqueVector.
+#[derive(Clone,Debug)]
+pub struct UniqueVector {
+    vector: RefCell<Vec<Rc<KlElement>>>
+}
+
+#[derive(Clone,Debug)]
+pub enum KlStreamDirection {
+    In,
+    Out
+}
+
+#[derive(Debug)]
+pub struct KlFileStream {
+    direction : KlStreamDirection,
+    file: RefCell<File>
+}
+
+#[derive(Clone,Debug)]
+pub enum KlStdStream {
+    Stdout,
+    Stdin
+}
+
+#[derive(Debug)]
+pub enum KlStream {
+    FileStream(KlFileStream),
+    Std(KlStdStream)
+}
+
+#[derive(Clone,Debug)]
+pub enum KlElement {
+    Symbol(KlSymbol),
+    Number(KlNumber),
+    String(String),
+    Cons(Rc<KlElement>, Rc<KlElement>),
+    Closure(KlClosure),
+    Vector(Rc<UniqueVector>),
+    Stream(Rc<KlStream>),
+    Nil,
+    Recur(Vec<Rc<KlElement>>),
+    Exception(Rc<KlError>)
+}
+
+// Dropping an element takes apart the conses, vectors and recur arguments
+// nobody else holds using a work list, so neither a long list nor a deeply
+// nested one recurses once per cell.
+impl Drop for KlElement {
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        shen_detach_unshared_children(self, &mut pending);
+        while let Some(child) = pending.pop() {
+            if let Ok(mut child) = Rc::try_unwrap(child) {
+                shen_detach_unshared_children(&mut child, &mut pending);
+            }
+        }
+    }
+}
+
+fn shen_detach_unshared_children(element: &mut KlElement, pending: &mut Vec<Rc<KlElement>>) {
+    match element {
+        &mut KlElement::Cons(ref mut head, ref mut tail) => {
+            if Rc::strong_count(head) == 1 {
+                pending.push(std::mem::replace(head, shen_nil()));
+            }
+            if Rc::strong_count(tail) == 1 {
+                pending.push(std::mem::replace(tail, shen_nil()));
+            }
+        },
+        &mut KlElement::Vector(ref mut vector) => {
+            if let Some(vector) = Rc::get_mut(vector) {
+                pending.extend(vector.vector.get_mut().drain(..).filter(| e | Rc::strong_count(e) == 1));
+            }
+        },
+        &mut KlElement::Recur(ref mut arguments) => pending.extend(arguments.drain(..)),
+        _ => ()
+    }
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum KlErrorKind {
+    // Raised by simple-error.
+    User,
+    // A primitive was given a value it cannot work with.
+    Type,
+    // Something that takes no more arguments, or is not a function at all,
+    // was applied.
+    Arity,
+    // Opening, reading, writing or closing a stream failed.
+    Io,
+    // A function that was never defined was called.
+    Undefined,
+    // The native stack ran out; see shen_stack_exhausted.
+    StackOverflow,
+    // A value was needed from something that produced none.
+    NoValue,
+    // Anything else the runtime gives up on, like an unbound global or a
+    // division by zero.
+    Runtime
+}
+
+impl KlErrorKind {
+    // The symbol shen-rust.error-kind gives for this kind.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            KlErrorKind::User => "user",
+            KlErrorKind::Type => "type",
+            KlErrorKind::Arity => "arity",
+            KlErrorKind::Io => "io",
+            KlErrorKind::Undefined => "undefined",
+            KlErrorKind::StackOverflow => "stack-overflow",
+            KlErrorKind::NoValue => "no-value",
+            KlErrorKind::Runtime => "runtime"
+        }
+    }
+}
+
+// origin is the primitive or special form that raised the error. trace
+// holds the functions the error has unwound through so far, innermost
+// first, up to ERROR_TRACE_LIMIT of them; trace_omitted counts the rest.
+// site is where in the function it is unwinding through now the error was
+// raised or came out of a call, when the source of that function is known.
+#[derive(Debug,Clone)]
+pub struct KlError {
+    pub kind: KlErrorKind,
+    pub message: String,
+    pub origin: Option<KlSymbol>,
+    pub trace: Vec<KlTraceEntry>,
+    pub trace_omitted: usize,
+    pub site: Option<KlSpan>
+}
+
+// A function an error unwound through, and the site the error left it from.
+#[derive(Debug,Clone)]
+pub struct KlTraceEntry {
+    pub function: KlSymbol,
+    pub site: Option<KlSpan>
+}
+
+const ERROR_TRACE_LIMIT : usize = 32;
+
+impl KlError {
+    pub fn new(kind: KlErrorKind, origin: Option<KlSymbol>, message: String) -> KlError {
+        KlError { kind: kind, message: message, origin: origin, trace: Vec::new(), trace_omitted: 0, site: None }
+    }
+
+    // The text error-to-string gives for this error.
+    pub fn message(&self) -> String {
+        match self.origin {
+            Some(origin) => format!("{}: {}", shen_symbol_name(origin), self.message),
+            None => self.message.clone()
+        }
+    }
+}
+
+impl fmt::Display for KlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.message(), shen_format_error_trace(self))
+    }
+}
+
+#[derive(Debug,Clone,PartialEq)]
+pub struct KlSpan {
+    pub file: Option<Rc<String>>,
+    pub line: usize,
+    pub column: usize
+}
+
+impl KlSpan {
+    pub fn new(file: Option<&str>, line: usize, column: usize) -> KlSpan {
+        KlSpan { file: file.map(| f | Rc::new(String::from(f))), line: line, column: column }
+    }
+}
+
+impl fmt::Display for KlSpan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column)
+        }
+    }
+}
+
+// The span of a form and, for a list, the spans of its elements, so that
+// the span of any subform can be found by the path the rest of the crate
+// uses to address it.
+#[derive(Debug,Clone,PartialEq)]
+pub struct KlSpanTree {
+    pub span: KlSpan,
+    pub children: Vec<Rc<KlSpanTree>>
+}
+
+// Spans for a form made of the given elements of the form spans belong to,
+// in that order.
+pub fn shen_spans_from(spans: Option<&Rc<KlSpanTree>>, elements: &[usize]) -> Option<Rc<KlSpanTree>> {
+    let spans = spans?;
+    let children = elements.iter().map(| &i | spans.children.get(i).cloned()).collect::<Option<Vec<_>>>()?;
+    Some(Rc::new(KlSpanTree { span: spans.span.clone(), children: children }))
+}
+
+// The spans of the subform at path, when the spans of the form are known.
+pub fn shen_spans_at<'a>(spans: Option<&'a Rc<KlSpanTree>>, path: &[usize]) -> Option<&'a Rc<KlSpanTree>> {
+    let mut spans = spans?;
+    for &index in path {
+        spans = spans.children.get(index)?;
+    }
+    Some(spans)
+}
+
+// Done(Ok(None)) is "no value", which is never the empty list: the empty
+// list is always Done(Ok(Some(shen_nil()))). Trampoline is a call made in
+// tail position that has not been run yet; see shen_run_trampoline.
+#[derive(Clone)]
+pub enum KlClosure {
+    FeedMe(Rc<Fn(Rc<KlElement>) -> KlClosure>),
+    Thunk(Rc<Fn() -> Rc<KlElement>>),
+    Done(Result<Option<Rc<KlElement>>,Rc<KlError>>),
+    Trampoline(Rc<Fn() -> Rc<KlElement>>)
+}
+
+impl fmt::Debug for KlClosure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &KlClosure::Done(ref s) => write!(f, "{:?}", s.clone()),
+            &KlClosure::Thunk(_) => write!(f, "Thunk"),
+            &KlClosure::FeedMe(_) => write!(f, "Unsaturated"),
+            &KlClosure::Trampoline(_) => write!(f, "Trampoline"),
+        }
+    }
+}
+// Token\ Types:1 ends here
+
+// [[file:../shen-rust.org::*Symbol%20Interner][Symbol\ Interner:1]]
+// Like the tables below, the interner belongs to one thread, so interning
+// takes no lock. A symbol's id only means something on the thread that
+// interned it, which is why KlSymbol (and so every token) is not Send.
+// kl_ids remembers names before renaming, so interning a name seen
+// before costs one lookup instead of a shen_rename_symbol.
+struct KlSymbolInterner {
+    ids: HashMap<Rc<str>, KlSymbol>,
+    kl_ids: HashMap<Rc<str>, KlSymbol>,
+    names: Vec<Rc<str>>
+}
+
+// Interned in this order on every thread, so each has the id of its
+// constant in impl KlSymbol.
+const PREINTERNED_SYMBOLS: &'static [ &'static str ] =
+    &["true", "false", "shen.fail!", "defun", "lambda", "let", "if", "cond", "do", "and", "or", "freeze", "trap-error",
+      "in", "run", "real"];
+
+thread_local!(static SYMBOL_INTERNER: RefCell<KlSymbolInterner> = {
+    let mut interner = KlSymbolInterner { ids: HashMap::new(), kl_ids: HashMap::new(), names: Vec::new() };
+    for name in PREINTERNED_SYMBOLS {
+        interner.intern_kl_name(name);
+    }
+    RefCell::new(interner)
+});
+
+impl KlSymbolInterner {
+    fn intern(&mut self, name: &str) -> KlSymbol {
+        match self.ids.get(name) {
+            Some(symbol) => *symbol,
+            None => {
+                let name : Rc<str> = Rc::from(name);
+                let symbol = KlSymbol(self.names.len() as u32, PhantomData);
+                self.ids.insert(name.clone(), symbol);
+                self.names.push(name);
+                symbol
+            }
+        }
+    }
+
+    fn intern_kl_name(&mut self, kl_name: &str) -> KlSymbol {
+        match self.kl_ids.get(kl_name) {
+            Some(symbol) => *symbol,
+            None => {
+                let symbol = self.intern(&shen_rename_symbol(String::from(kl_name)));
+                self.kl_ids.insert(Rc::from(kl_name), symbol);
+                symbol
+            }
+        }
+    }
+}
+
+impl KlSymbol {
+    pub const TRUE : KlSymbol = KlSymbol(0, PhantomData);
+    pub const FALSE : KlSymbol = KlSymbol(1, PhantomData);
+    pub const FAIL : KlSymbol = KlSymbol(2, PhantomData);
+    pub const DEFUN : KlSymbol = KlSymbol(3, PhantomData);
+    pub const LAMBDA : KlSymbol = KlSymbol(4, PhantomData);
+    pub const LET : KlSymbol = KlSymbol(5, PhantomData);
+    pub const IF : KlSymbol = KlSymbol(6, PhantomData);
+    pub const COND : KlSymbol = KlSymbol(7, PhantomData);
+    pub const DO : KlSymbol = KlSymbol(8, PhantomData);
+    pub const AND : KlSymbol = KlSymbol(9, PhantomData);
+    pub const OR : KlSymbol = KlSymbol(10, PhantomData);
+    pub const FREEZE : KlSymbol = KlSymbol(11, PhantomData);
+    pub const TRAP_ERROR : KlSymbol = KlSymbol(12, PhantomData);
+    pub const IN : KlSymbol = KlSymbol(13, PhantomData);
+    pub const RUN : KlSymbol = KlSymbol(14, PhantomData);
+    pub const REAL : KlSymbol = KlSymbol(15, PhantomData);
+
+    // name is the mangled name, as produced by shen_rename_symbol.
+    pub fn new(name: &str) -> KlSymbol {
+        SYMBOL_INTERNER.with(| interner | interner.borrow_mut().intern(name))
+    }
+
+    // kl_name is the name as Shen writes it.
+    pub fn intern(kl_name: &str) -> KlSymbol {
+        SYMBOL_INTERNER.with(| interner | interner.borrow_mut().intern_kl_name(kl_name))
+    }
+
+    pub fn from_bool(b: bool) -> KlSymbol {
+        if b { KlSymbol::TRUE } else { KlSymbol::FALSE }
+    }
+
+    pub fn as_str(&self) -> Rc<str> {
+        SYMBOL_INTERNER.with(| interner | interner.borrow().names[self.0 as usize].clone())
+    }
+}
+
+impl fmt::Debug for KlSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+// Symbol\ Interner:1 ends here
+
+// [[file:../shen-rust.org::*Symbol%20Table][Symbol\ Table:1]]
+thread_local!(static SYMBOL_TABLE: RefCell<HashMap<KlSymbol, Rc<KlElement>>> = RefCell::new(HashMap::new()));
+// Symbol\ Table:1 ends here
+
+// [[file:../shen-rust.org::*Function%20Table][Function\ Table:1]]
+thread_local!(static FUNCTION_TABLE: RefCell<HashMap<KlSymbol, KlClosure>> = RefCell::new(HashMap::new()));
+
+// The names shen_fill_function_table gave the primitives, so they can be
+// told apart from functions defined in Shen.
+thread_local!(static PRIMITIVE_NAMES: RefCell<HashSet<KlSymbol>> = RefCell::new(HashSet::new()));
+
+pub fn shen_is_primitive(s: KlSymbol) -> bool {
+    PRIMITIVE_NAMES.with(| names | names.borrow().contains(&s))
+}
+// Function\ Table:1 ends here
+
+// [[file:../shen-rust.org::*Stack%20Depth][Stack\ Depth:1]]
+// Where the stack of this thread starts and how much of it Shen may use,
+// when it is known. The rest is kept back so that a call refused for lack of
+// stack can still build its error and unwind to a trap-error.
+thread_local!(static STACK_BOUNDS: Cell<Option<(usize, usize)>> = Cell::new(None));
+
+fn shen_stack_address() -> usize {
+    let marker = 0u8;
+    &marker as *const u8 as usize
+}
+
+// Runs f on a new thread with a stack of stack_size bytes and the stack
+// depth check turned on. Symbols and the symbol and function tables belong
+// to the thread, so the kernel has to be loaded, and any forms built,
+// inside f.
+pub fn shen_spawn_with_stack<F, T>(stack_size: usize, f: F) -> io::Result<thread::JoinHandle<T>>
+    where F: FnOnce() -> T + Send + 'static, T: Send + 'static {
+    thread::Builder::new().stack_size(stack_size).spawn(move || {
+        STACK_BOUNDS.with(| bounds | bounds.set(Some((shen_stack_address(), stack_size / 8 * 7))));
+        f()
+    })
+}
+
+pub fn shen_stack_exhausted() -> bool {
+    STACK_BOUNDS.with(| bounds | {
+        match bounds.get() {
+            Some((base, usable)) => {
+                let here = shen_stack_address();
+                let used = if base > here { base - here } else { here - base };
+                used > usable
+            },
+            None => false
+        }
+    })
+}
+
+pub fn shen_stack_overflow_error() -> Rc<KlError> {
+    Rc::new(KlError::new(KlErrorKind::StackOverflow, None, String::from("stack depth exceeded.")))
+}
+// Stack\ Depth:1 ends here
+
+// [[file:../shen-rust.org::*Symbol%20Character%20Rename%20Table][Symbol\ Character\ Rename\ Table:1]]
+thread_local!(static SYMBOL_CHAR_RENAME_TABLE: HashMap<char, &'static str> = {
+    let mut table = HashMap::new();
+    table.insert('=' ,"__Equal__");
+    table.insert('-' ,"__Dash__");
+    table.insert('*' ,"__Star__");
+    table.insert('/' ,"__Slash__");
+    table.insert('+' ,"__Plus__");
+    table.insert('?' ,"__Question__");
+    table.insert('$' ,"__Dollar__");
+    table.insert('!' ,"__Bang__");
+    table.insert('@' ,"__At__");
+    table.insert('~' ,"__Tilde__");
+    table.insert('.' ,"__Dot__");
+    table.insert('>' ,"__GT__");
+    table.insert('<' ,"__LT__");
+    table.insert('&' ,"__And__");
+    table.insert('%' ,"__Percent__");
+    table.insert('\'',"__Tick__");
+    table.insert('#' ,"__Hash__");
+    table.insert('`' ,"__BackTick__");
+    table.insert(';' ,"__Semi__");
+    table.insert(':' ,"__Colon__");
+    table.insert('{' ,"__CurlyL__");
+    table.insert('}' ,"__CurlyR__");
+    table.insert('^' ,"__Caret__");
+    table.insert('|' ,"__Bar__");
+    table.insert('_' ,"__Under__");
+    table
+});
+
+thread_local!(static SYMBOL_CHAR_UNRENAME_TABLE: HashMap<&'static str,char> = {
+    let mut table = HashMap::new();
+    table.insert("__Equal__"    ,'=');
+    table.insert("__Dash__"     ,'-');
+    table.insert("__Star__"     ,'*');
+    table.insert("__Slash__"    ,'/');
+    table.insert("__Plus__"     ,'+');
+    table.insert("__Question__" ,'?');
+    table.insert("__Dollar__"   ,'$');
+    table.insert("__Bang__"     ,'!');
+    table.insert("__At__"       ,'@');
+    table.insert("__Tilde__"    ,'~');
+    table.insert("__Dot__"      ,'.');
+    table.insert("__GT__"       ,'>');
+    table.insert("__LT__"       ,'<');
+    table.insert("__And__"      ,'&');
+    table.insert("__Percent__"  ,'%');
+    table.insert("__Tick__"     ,'\'');
+    table.insert("__Hash__"     ,'#');
+    table.insert("__BackTick__" ,'`');
+    table.insert("__Semi__"     ,';');
+    table.insert("__Colon__"    ,':');
+    table.insert("__CurlyL__"   ,'{');
+    table.insert("__CurlyR__"   ,'}');
+    table.insert("__Caret__"    ,'^');
+    table.insert("__Bar__"      ,'|');
+    table.insert("__Under__"    ,'_');
+    table
+    });
+// Symbol\ Character\ Rename\ Table:1 ends here
+
+// [[file:../shen-rust.org::*Symbol%20Keyword%20Rename%20Table][Symbol\ Keyword\ Rename\ Table:1]]
+thread_local!(static SYMBOL_KEYWORD_RENAME_TABLE: HashMap<&'static str, &'static str> = {
+    let mut table = HashMap::new();
+    table.insert("abstract" ,"shen_abstract");
+    table.insert("alignof"  ,"shen_alignof");
+    table.insert("as"       ,"shen_as");
+    table.insert("async"    ,"shen_async");
+    table.insert("await"    ,"shen_await");
+    table.insert("become"   ,"shen_become");
+    table.insert("box"      ,"shen_box");
+    table.insert("break"    ,"shen_break");
+    table.insert("const"    ,"shen_const");
+    table.insert("continue" ,"shen_continue");
+    table.insert("crate"    ,"shen_crate");
+    table.insert("do"       ,"shen_do");
+    table.insert("dyn"      ,"shen_dyn");
+    table.insert("else"     ,"shen_else");
+    table.insert("enum"     ,"shen_enum");
+    table.insert("extern"   ,"shen_extern");
+    table.insert("false"    ,"shen_false");
+    table.insert("final"    ,"shen_final");
+    table.insert("fn"       ,"shen_fn");
+    table.insert("for"      ,"shen_for");
+    table.insert("gen"      ,"shen_gen");
+    table.insert("if"       ,"shen_if");
+    table.insert("impl"     ,"shen_impl");
+    table.insert("in"       ,"shen_in");
+    table.insert("let"      ,"shen_let");
+    table.insert("loop"     ,"shen_loop");
+    table.insert("macro"    ,"shen_macro");
+    table.insert("match"    ,"shen_match");
+    table.insert("mod"      ,"shen_mod");
+    table.insert("move"     ,"shen_move");
+    table.insert("mut"      ,"shen_mut");
+    table.insert("offsetof" ,"shen_offsetof");
+    table.insert("override" ,"shen_override");
+    table.insert("priv"     ,"shen_priv");
+    table.insert("proc"     ,"shen_proc");
+    table.insert("pub"      ,"shen_pub");
+    table.insert("pure"     ,"shen_pure");
+    table.insert("ref"      ,"shen_ref");
+    table.insert("return"   ,"shen_return");
+    table.insert("Self"     ,"shen_Self");
+    table.insert("self"     ,"shen_self");
+    table.insert("sizeof"   ,"shen_sizeof");
+    table.insert("static"   ,"shen_static");
+    table.insert("struct"   ,"shen_struct");
+    table.insert("super"    ,"shen_super");
+    table.insert("trait"    ,"shen_trait");
+    table.insert("true"     ,"shen_true");
+    table.insert("try"      ,"shen_try");
+    table.insert("type"     ,"shen_type");
+    table.insert("typeof"   ,"shen_typeof");
+    table.insert("union"    ,"shen_union");
+    table.insert("unsafe"   ,"shen_unsafe");
+    table.insert("unsized"  ,"shen_unsized");
+    table.insert("use"      ,"shen_use");
+    table.insert("virtual"  ,"shen_virtual");
+    table.insert("where"    ,"shen_where");
+    table.insert("while"    ,"shen_while");
+    table.insert("yield"    ,"shen_yield");
+    table
+});
+
+thread_local!(static SYMBOL_KEYWORD_UNRENAME_TABLE: HashMap<&'static str, &'static str> = {
+    let mut table = HashMap::new();
+    table.insert("shen_abstract" ,"abstract");
+    table.insert("shen_alignof"  ,"alignof");
+    table.insert("shen_as"       ,"as");
+    table.insert("shen_async"    ,"async");
+    table.insert("shen_await"    ,"await");
+    table.insert("shen_become"   ,"become");
+    table.insert("shen_box"      ,"box");
+    table.insert("shen_break"    ,"break");
+    table.insert("shen_const"    ,"const");
+    table.insert("shen_continue" ,"continue");
+    table.insert("shen_crate"    ,"crate");
+    table.insert("shen_do"       ,"do");
+    table.insert("shen_dyn"      ,"dyn");
+    table.insert("shen_else"     ,"else");
+    table.insert("shen_enum"     ,"enum");
+    table.insert("shen_extern"   ,"extern");
+    table.insert("shen_false"    ,"false");
+    table.insert("shen_final"    ,"final");
+    table.insert("shen_fn"       ,"fn");
+    table.insert("shen_for"      ,"for");
+    table.insert("shen_gen"      ,"gen");
+    table.insert("shen_if"       ,"if");
+    table.insert("shen_impl"     ,"impl");
+    table.insert("shen_in"       ,"in");
+    table.insert("shen_let"      ,"let");
+    table.insert("shen_loop"     ,"loop");
+    table.insert("shen_macro"    ,"macro");
+    table.insert("shen_match"    ,"match");
+    table.insert("shen_mod"      ,"mod");
+    table.insert("shen_move"     ,"move");
+    table.insert("shen_mut"      ,"mut");
+    table.insert("shen_offsetof" ,"offsetof");
+    table.insert("shen_override" ,"override");
+    table.insert("shen_priv"     ,"priv");
+    table.insert("shen_proc"     ,"proc");
+    table.insert("shen_pub"      ,"pub");
+    table.insert("shen_pure"     ,"pure");
+    table.insert("shen_ref"      ,"ref");
+    table.insert("shen_return"   ,"return");
+    table.insert("shen_Self"     ,"Self");
+    table.insert("shen_self"     ,"self");
+    table.insert("shen_sizeof"   ,"sizeof");
+    table.insert("shen_static"   ,"static");
+    table.insert("shen_struct"   ,"struct");
+    table.insert("shen_super"    ,"super");
+    table.insert("shen_trait"    ,"trait");
+    table.insert("shen_true"     ,"true");
+    table.insert("shen_try"      ,"try");
+    table.insert("shen_type"     ,"type");
+    table.insert("shen_typeof"   ,"typeof");
+    table.insert("shen_union"    ,"union");
+    table.insert("shen_unsafe"   ,"unsafe");
+    table.insert("shen_unsized"  ,"unsized");
+    table.insert("shen_use"      ,"use");
+    table.insert("shen_virtual"  ,"virtual");
+    table.insert("shen_where"    ,"where");
+    table.insert("shen_while"    ,"while");
+    table.insert("shen_yield"    ,"yield");
+    table
+});
+// Symbol\ Keyword\ Rename\ Table:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+// Symbols become Rust identifiers: a keyword becomes shen_<keyword>,
+// otherwise letters (and digits after the first character) are kept and
+// every other character, '_' included, becomes a __Name__ or __U<hex>__
+// escape. Escape names are alphanumeric and a bare '_' only appears in
+// escapes or after the keyword prefix, so the mangling is injective and
+// shen_unrename_symbol inverts it.
+pub fn shen_rename_symbol(symbol : String) -> String {
+    SYMBOL_KEYWORD_RENAME_TABLE.with ( | table | {
+        match table.get(symbol.as_str()) {
+            Some(renamed) => String::from(*renamed),
+            None => {
+                SYMBOL_CHAR_RENAME_TABLE.with(| table | {
+                    let mut result = String::new();
+                    for (i, c) in symbol.chars().enumerate() {
+                        match table.get(&c) {
+                            Some(renamed) => result.push_str(renamed),
+                            None if c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()) => result.push(c),
+                            None => result.push_str(&format!("__U{:X}__", c as u32))
+                        }
+                    }
+                    result
+                })
+            }
+        }
+    })
+}
+
+fn unescape_symbol_char(table: &HashMap<&'static str, char>, escape: &str) -> Option<char> {
+    match table.get(escape) {
+        Some(c) => Some(*c),
+        None if escape.starts_with("__U") && escape.len() > 5 => {
+            u32::from_str_radix(&escape[3..escape.len() - 2], 16).ok().and_then(std::char::from_u32)
+        },
+        None => None
+    }
+}
+
+pub fn shen_unrename_symbol(s : String) -> String {
+    SYMBOL_KEYWORD_UNRENAME_TABLE.with(|table|{
+        match table.get(s.as_str()) {
+            Some(unrenamed) => String::from(*unrenamed),
+            None => {
+                SYMBOL_CHAR_UNRENAME_TABLE.with(|table| {
+                    let mut result = String::new();
+                    let mut rest = s.as_str();
+                    while let Some(start) = rest.find("__") {
+                        result.push_str(&rest[..start]);
+                        rest = &rest[start..];
+                        let escape = rest[2..].find("__").map(| end | &rest[..end + 4]);
+                        match escape.and_then(| e | unescape_symbol_char(table, e).map(| c | (c, e.len()))) {
+                            Some((c, length)) => {
+                                result.push(c);
+                                rest = &rest[length..];
+                            },
+                            None => {
+                                result.push_str("__");
+                                rest = &rest[2..];
+                            }
+                        }
+                    }
+                    result.push_str(rest);
+                    result
+                })
+            }
+        }
+    })
+}
+
+pub fn shen_intern_symbol(name: String) -> KlSymbol {
+    KlSymbol::intern(&name)
+}
+
+pub fn shen_symbol_name(symbol: KlSymbol) -> String {
+    shen_unrename_symbol(symbol.as_str().to_string())
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*Constants][Constants:1]]
+const CHARACTERS: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ=-*/+_?$!@~.><&%'#`;:{}^|";
+const DIGITS: &'static str = "0123456789";
+// Constants:1 ends here
+
+// [[file:../shen-rust.org::*Parser][Parser:1]]
+named!(klsymbol<KlToken>,
+       chain!(
+       initial: one_of!(CHARACTERS) ~
+       remainder: many0!(
+           alt_complete!(
+               one_of!(DIGITS) |
+               one_of!(CHARACTERS)
+           )
+       ),
+       || {
+           let mut res : Vec <char> = vec![initial];
+           res.extend(remainder);
+           KlToken::Symbol(shen_intern_symbol(res.into_iter().collect()))
+       })
+);
+// Parser:1 ends here
+
+// [[file:../shen-rust.org::*Parsers][Parsers:1]]
+named!(klnumber<KlToken>,
+       map!(map_res!(klnumbertoken, make_number), KlToken::Number)
+);
+
+// A numeric literal has to make up the whole token; a token that merely
+// starts like one, such as 1.2.3 or 1-, is read by klnumericsymbol.
+named!(klnumbertoken< &[u8] >,
+       chain!(
+           literal: klnumberliteral ~
+           peek!(kltokenend),
+           || literal
+       )
+);
+
+named!(klnumericsymbol<KlToken>,
+       chain!(
+           not!(call!(klnumbertoken)) ~
+           token: recognize!(many1!(alt_complete!(one_of!(DIGITS) | one_of!(CHARACTERS)))),
+           || KlToken::Symbol(shen_intern_symbol(String::from_utf8_lossy(token).into_owned()))
+       )
+);
+
+fn kltokenend(input: &[u8]) -> IResult<&[u8], ()> {
+    match input.first() {
+        None => IResult::Done(input, ()),
+        Some(&b) if (b as char).is_whitespace() || b == b'(' || b == b')' => IResult::Done(input, ()),
+        Some(_) => IResult::Error(Err::Position(ErrorKind::Custom(0), input))
+    }
+}
+
+named!(klnumberliteral< &[u8] >,
+       recognize!(
+           chain!(
+               many0!(one_of!("-+")) ~
+               alt_complete!(
+                   chain!(
+                       many1!(one_of!(DIGITS)) ~
+                       opt!(complete!(chain!(char!('.') ~ many1!(one_of!(DIGITS)), || ()))),
+                       || ()
+                   ) |
+                   chain!(
+                       char!('.') ~
+                       many1!(one_of!(DIGITS)),
+                       || ()
+                   )
+               ) ~
+               opt!(complete!(klexponent)),
+               || ()
+           )
+       )
+);
+
+named!(klexponent< &[u8] >,
+       recognize!(
+           chain!(
+               char!('e') ~
+               many0!(one_of!("-+")) ~
+               many1!(one_of!(DIGITS)),
+               || ()
+           )
+       )
+);
+// Parsers:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+fn split_signs(literal: &str) -> (bool, &str) {
+    let unsigned = literal.trim_start_matches(| c | c == '-' || c == '+');
+    let negative = literal[.. literal.len() - unsigned.len()].matches('-').count() % 2 == 1;
+    (negative, unsigned)
+}
+
+// The largest power of ten an integer literal may be scaled by: past it the
+// reader would spend its time building an integer nobody can print.
+const MAX_INTEGER_EXPONENT : i32 = 4096;
+
+fn make_number(literal: &[u8]) -> Result<KlNumber, String> {
+    let literal = str::from_utf8(literal).map_err(| _ | String::from("number literal is not valid UTF-8"))?;
+    let (negative, unsigned) = split_signs(literal);
+    let (mantissa, exponent) = match unsigned.find('e') {
+        Some(i) => {
+            let (exponent_negative, exponent_digits) = split_signs(&unsigned[i + 1 ..]);
+            let exponent = exponent_digits.parse::<i32>().map_err(| _ | format!("exponent of {} is out of range", literal))?;
+            (&unsigned[.. i], if exponent_negative { -exponent } else { exponent })
+        },
+        None => (unsigned, 0)
+    };
+    let sign = if negative { "-" } else { "" };
+    if mantissa.contains('.') || exponent < 0 {
+        let float = format!("{}0{}e{}", sign, mantissa, exponent).parse::<f64>()
+            .map_err(| _ | format!("{} is not a valid float", literal))?;
+        if float.is_finite() {
+            Ok(KlNumber::Float(float))
+        }
+        else {
+            Err(format!("float literal {} is out of range", literal))
+        }
+    }
+    else if exponent > MAX_INTEGER_EXPONENT {
+        Err(format!("exponent of {} is out of range", literal))
+    }
+    else {
+        let int = format!("{}{}", sign, mantissa).parse::<i64>().ok()
+            .and_then(| i | 10i64.checked_pow(exponent as u32).and_then(| e | i.checked_mul(e)));
+        match int {
+            Some(i) => Ok(KlNumber::Int(i)),
+            None => {
+                let big = format!("{}{}", sign, mantissa).parse::<BigInt>()
+                    .map_err(| _ | format!("{} is not a valid integer", literal))?;
+                Ok(shen_demote_big(big * pow(BigInt::from(10), exponent as usize)))
+            }
+        }
+    }
+}
+
+fn number_error_message(input: &[u8]) -> Option<String> {
+    match klnumbertoken(input) {
+        IResult::Done(_, literal) => make_number(literal).err(),
+        _ => None
+    }
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*Parsers][Parsers:1]]
+named!(klstring<KlToken>,
+       map!(map_res!(klstringcontents, make_string), KlToken::String)
+);
+
+named!(klstringcontents< Vec<&[u8]> >,
+       chain!(
+           char!('\"') ~
+           contents:  many0!(klstringinnards) ~
+           char!('\"'),
+           || contents
+       )
+);
+
+named!(klstringinnards< &[u8] >,
+       escaped!(none_of!("\"\\"), '\\', klstringescape)
+);
+
+named!(klstringescape< &[u8] >,
+       alt_complete!(tag!("\"") | tag!("\\") | tag!("n") | tag!("t") | tag!("r") | tag!("u"))
+);
+// Parsers:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+fn make_string (contents:Vec<&[u8]>) -> Result<String, String> {
+    let to_vectors : Vec< Vec<u8> > = contents.iter().map(|c| c.to_vec()).collect();
+    let smushed : Vec<u8> = to_vectors.concat();
+    let mut unescaped : Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < smushed.len() {
+        match smushed[i] {
+            b'\\' => {
+                let (c, width) = match smushed.get(i + 1) {
+                    Some(&b'n') => ('\n', 2),
+                    Some(&b't') => ('\t', 2),
+                    Some(&b'r') => ('\r', 2),
+                    Some(&b'u') => {
+                        let hex = smushed.get(i + 2 .. i + 6)
+                            .filter(| h | h.iter().all(u8::is_ascii_hexdigit))
+                            .and_then(| h | str::from_utf8(h).ok());
+                        match hex.and_then(| h | u32::from_str_radix(h, 16).ok()).and_then(std::char::from_u32) {
+                            Some(c) => (c, 6),
+                            None => return Err(String::from("\\u expects four hex digits naming a character"))
+                        }
+                    },
+                    Some(&escaped) => (escaped as char, 2),
+                    None => return Err(String::from("dangling '\\' in string"))
+                };
+                unescaped.extend(c.to_string().as_bytes());
+                i = i + width;
+            },
+            b'c' if smushed.get(i + 1) == Some(&b'#') => {
+                let digits = smushed[i + 2 ..].iter().take_while(| b | (**b as char).is_digit(10)).count();
+                if digits > 0 && smushed.get(i + 2 + digits) == Some(&b';') {
+                    let code = str::from_utf8(&smushed[i + 2 .. i + 2 + digits]).ok().and_then(| d | d.parse::<u32>().ok());
+                    match code.and_then(std::char::from_u32) {
+                        Some(c) => unescaped.extend(c.to_string().as_bytes()),
+                        None => return Err(format!("c#{}; does not name a character", String::from_utf8_lossy(&smushed[i + 2 .. i + 2 + digits])))
+                    }
+                    i = i + digits + 3;
+                }
+                else {
+                    unescaped.push(b'c');
+                    i = i + 1;
+                }
+            },
+            b => {
+                unescaped.push(b);
+                i = i + 1;
+            }
+        }
+    }
+    String::from_utf8(unescaped).map_err(| e | format!("invalid UTF-8 in string at byte {}", e.utf8_error().valid_up_to()))
+}
+
+fn string_error_message(input: &[u8]) -> String {
+    match klstringcontents(input) {
+        IResult::Done(_, contents) => {
+            match make_string(contents) {
+                Err(message) => message,
+                Ok(_) => String::from("malformed string")
+            }
+        },
+        IResult::Incomplete(_) => String::from("unterminated string"),
+        IResult::Error(_) => {
+            let mut i = 1;
+            while i < input.len() && input[i] != b'"' {
+                if input[i] == b'\\' {
+                    match input.get(i + 1) {
+                        None => return String::from("unterminated string"),
+                        Some(escaped) if !b"\"\\ntru".contains(escaped) =>
+                            return format!("unknown escape '\\{}' in string", *escaped as char),
+                        _ => i = i + 1
+                    }
+                }
+                i = i + 1;
+            }
+            String::from("malformed string")
+        }
+    }
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*Many%20Until%20Combinator][Many\ Until\ Combinator:1]]
+#[macro_export]
+macro_rules! many0_until (
+    ($input:expr, $stopmac:ident!( $($args:tt)* ), $submac:ident!( $($args2:tt)* )) => (
+        {
+            let mut res = Vec::new();
+            let mut input = $input;
+            let mut loop_result = Ok(());
+
+            while input.input_len() != 0 {
+                match $stopmac!(input, $($args)*) {
+                    IResult::Error(_) => {
+                        match $submac!(input, $($args2)*) {
+                            IResult::Error(_) => {
+                                break;
+                            },
+                            IResult::Incomplete(Needed::Unknown) => {
+                                loop_result = Err(IResult::Incomplete(Needed::Unknown));
+                                break;
+                            },
+                            IResult::Incomplete(Needed::Size(i)) => {
+                                let size = i + ($input).input_len() - input.input_len();
+                                loop_result = Err(IResult::Incomplete(Needed::Size(size)));
+                                break;
+                            },
+                            IResult::Done(i, o) => {
+                                res.push(o);
+                                input = i;
+                            }
+                        }
+                    },
+                    IResult::Done(_,_) => {
+                        break;
+                    }
+                    IResult::Incomplete(Needed::Unknown) => {
+                        loop_result = Err(IResult::Incomplete(Needed::Unknown));
+                        break;
+                    },
+                    IResult::Incomplete(Needed::Size(i)) => {
+                        let size = i + ($input).input_len() - input.input_len();
+                        loop_result = Err(IResult::Incomplete(Needed::Size(size)));
+                        break;
+                    },
+                }
+            }
+            match loop_result {
+                Ok(()) => IResult::Done(input,res),
+                Err(e) => e
+            }
+        }
+    );
+    ($i:expr, $stopmac:ident!( $($args:tt)* ), $p:expr) => (
+        many0_until!($i, $stopmac!($($args)*), call!($p));
+    );
+);
+// Many\ Until\ Combinator:1 ends here
+
+// [[file:../shen-rust.org::*Parsers][Parsers:1]]
+fn skip_whitespace_and_comments(kl: &[u8], offset: usize) -> Result<usize, usize> {
+    let mut i = offset;
+    loop {
+        let rest = &kl[i..];
+        if rest.first().map(| &b | (b as char).is_whitespace()).unwrap_or(false) {
+            i = i + 1;
+        }
+        else if rest.starts_with(b"\\\\") {
+            i = match rest.iter().position(| &b | b == b'\n') {
+                Some(n) => i + n + 1,
+                None => kl.len()
+            };
+        }
+        else if rest.starts_with(b"\\*") {
+            match rest[2..].windows(2).position(| w | w == b"*\\") {
+                Some(n) => i = i + n + 4,
+                None => return Err(i)
+            }
+        }
+        else {
+            return Ok(i)
+        }
+    }
+}
+
+fn klspace(input: &[u8]) -> IResult<&[u8], ()> {
+    match skip_whitespace_and_comments(input, 0) {
+        Ok(i) => IResult::Done(&input[i..], ()),
+        Err(_) => IResult::Incomplete(Needed::Unknown)
+    }
+}
+
+named!(klsexp<KlToken>,
+       chain!(
+           char!('(') ~
+           klspace ~
+           inner: many0_until!(char!(')'), klsexpinnards) ~
+           char!(')'),
+           || {
+               KlToken::Cons(inner)
+           }
+       )
+);
+
+named!(klsexpinnards<KlToken>,
+       chain!(
+           klspace ~
+           atom: alt_complete!(klsexp|klatom) ~
+           klspace,
+           || atom
+       )
+);
+
+named!(klatom<KlToken>,
+       alt_complete!(klnumber|klstring|klsymbol|klnumericsymbol)
+);
+// Parsers:1 ends here
+
+// [[file:../shen-rust.org::*Parse%20Errors][Parse\ Errors:1]]
+const PARSE_ERROR_CONTEXT: usize = 40;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub message: String,
+    snippet_column: usize
+}
+
+impl ParseError {
+    pub fn new(kl: &[u8], offset: usize, message: String) -> ParseError {
+        let offset = std::cmp::min(offset, kl.len());
+        let before = &kl[..offset];
+        let line = before.iter().filter(| &&b | b == b'\n').count() + 1;
+        let line_start = before.iter().rposition(| &b | b == b'\n').map(| i | i + 1).unwrap_or(0);
+        let line_end = kl[offset..].iter().position(| &b | b == b'\n').map(| i | offset + i).unwrap_or(kl.len());
+        let column = String::from_utf8_lossy(&kl[line_start..offset]).chars().count();
+        let line_text : Vec<char> = String::from_utf8_lossy(&kl[line_start..line_end]).chars().collect();
+        let snippet_start = column.saturating_sub(PARSE_ERROR_CONTEXT);
+        let snippet_end = std::cmp::min(line_text.len(), column + PARSE_ERROR_CONTEXT);
+        ParseError {
+            file: None,
+            offset: offset,
+            line: line,
+            column: column + 1,
+            snippet: line_text[snippet_start..snippet_end].iter().cloned().collect(),
+            message: message,
+            snippet_column: column - snippet_start
+        }
+    }
+
+    pub fn in_file(self, file: &str) -> ParseError {
+        ParseError { file: Some(String::from(file)), .. self }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(f, "{}:", file)?,
+            None => ()
+        }
+        write!(f, "{}:{}: {}\n    {}\n    {}^",
+               self.line, self.column, self.message, self.snippet,
+               std::iter::repeat(" ").take(self.snippet_column).collect::<String>())
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn description(&self) -> &str {
+        self.message.as_str()
+    }
+}
+
+// The deepest the reader nests lists. klsexp and the passes over what it
+// reads recurse once per level, so a form nested deeper could run a thread
+// with the default 2MB stack out of it. The kernel nests 324 deep at most.
+const MAX_FORM_DEPTH : usize = 512;
+
+enum FormExtent {
+    Complete(usize),
+    // A form with a '(' at paren nested deeper than MAX_FORM_DEPTH, and its
+    // end if the input holds all of it.
+    TooDeep { paren: usize, end: Option<usize> },
+    // A toplevel atom that runs up to the end of the input, which may still
+    // continue it.
+    UnfinishedAtom(usize),
+    UnclosedParen(usize),
+    UnterminatedString(usize),
+    UnterminatedComment(usize)
+}
+
+fn form_extent(kl: &[u8], start: usize) -> FormExtent {
+    let mut open_parens = Vec::new();
+    let mut too_deep = None;
+    let mut i = start;
+    // Parsing even part of a form that is too deep would overflow, so that
+    // trumps whatever else is wrong with it.
+    let unless_too_deep = | extent, too_deep : Option<usize> | match too_deep {
+        Some(paren) => FormExtent::TooDeep { paren: paren, end: None },
+        None => extent
+    };
+    loop {
+        if i > start && open_parens.is_empty() {
+            return match too_deep {
+                Some(paren) => FormExtent::TooDeep { paren: paren, end: Some(i) },
+                None => FormExtent::Complete(i)
+            }
+        }
+        i = match skip_whitespace_and_comments(kl, i) {
+            Ok(i) => i,
+            Err(comment) => return unless_too_deep(FormExtent::UnterminatedComment(comment), too_deep)
+        };
+        match kl.get(i) {
+            None => {
+                return match open_parens.pop() {
+                    Some(paren) => unless_too_deep(FormExtent::UnclosedParen(paren), too_deep),
+                    None => FormExtent::Complete(i)
+                }
+            },
+            Some(&b'"') => {
+                let string_start = i;
+                i = i + 1;
+                while i < kl.len() && kl[i] != b'"' {
+                    i = i + if kl[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= kl.len() {
+                    return unless_too_deep(FormExtent::UnterminatedString(string_start), too_deep)
+                }
+            },
+            Some(&b'(') => {
+                open_parens.push(i);
+                if open_parens.len() > MAX_FORM_DEPTH && too_deep.is_none() {
+                    too_deep = Some(i);
+                }
+            },
+            Some(&b')') => { open_parens.pop(); },
+            _ if open_parens.is_empty() => {
+                let atom_start = i;
+                while i + 1 < kl.len() && !(kl[i + 1] as char).is_whitespace() && !b"()\"".contains(&kl[i + 1]) {
+                    i = i + 1;
+                }
+                if i + 1 == kl.len() {
+                    return FormExtent::UnfinishedAtom(atom_start)
+                }
+            },
+            _ => ()
+        }
+        i = i + 1;
+    }
+}
+
+fn sexp_error_offset<'a>(form: &'a [u8]) -> &'a [u8] {
+    match klsexp(form) {
+        IResult::Error(Err::Position(_, p)) | IResult::Error(Err::NodePosition(_, p, _)) => {
+            if p.len() < form.len() && p.first() == Some(&b'(') {
+                sexp_error_offset(p)
+            }
+            else {
+                p
+            }
+        },
+        _ => form
+    }
+}
+
+fn describe_sexp_error(kl: &[u8], form: &[u8]) -> ParseError {
+    let form_offset = kl.len() - form.len();
+    match klsexp(form) {
+        IResult::Incomplete(_) => {
+            match form_extent(kl, form_offset) {
+                FormExtent::UnclosedParen(i) => ParseError::new(kl, i, String::from("unclosed '('")),
+                FormExtent::UnterminatedString(i) => ParseError::new(kl, i, String::from("unterminated string")),
+                FormExtent::UnterminatedComment(i) => ParseError::new(kl, i, String::from("unterminated block comment")),
+                FormExtent::Complete(_) | FormExtent::TooDeep { .. } | FormExtent::UnfinishedAtom(_) =>
+                    ParseError::new(kl, kl.len(), String::from("unexpected end of input"))
+            }
+        },
+        _ => {
+            let rest = sexp_error_offset(form);
+            let offset = kl.len() - rest.len();
+            match String::from_utf8_lossy(&rest[..std::cmp::min(4, rest.len())]).chars().next() {
+                Some('"') => ParseError::new(kl, offset, string_error_message(rest)),
+                Some(c) => match number_error_message(rest) {
+                    Some(message) => ParseError::new(kl, offset, message),
+                    None => ParseError::new(kl, offset, format!("unexpected character '{}'", c))
+                },
+                None => ParseError::new(kl, offset, String::from("unexpected end of input"))
+            }
+        }
+    }
+}
+
+fn parse_kl_forms(kl: &[u8]) -> Result<Vec<(usize, KlToken)>, ParseError> {
+    let mut parsed = Vec::new();
+    let mut input = kl;
+    loop {
+        match skip_whitespace_and_comments(kl, kl.len() - input.len()) {
+            Ok(i) => input = &kl[i..],
+            Err(comment) => return Err(ParseError::new(kl, comment, String::from("unterminated block comment")))
+        }
+        let offset = kl.len() - input.len();
+        match input.first() {
+            None => return Ok(parsed),
+            Some(&b'(') => {
+                if let FormExtent::TooDeep { paren, .. } = form_extent(kl, offset) {
+                    return Err(ParseError::new(kl, paren, format!("lists nested more than {} deep", MAX_FORM_DEPTH)))
+                }
+                match klsexp(input) {
+                    IResult::Done(rest, sexp) => {
+                        parsed.push((offset, sexp));
+                        input = rest;
+                    },
+                    _ => return Err(describe_sexp_error(kl, input))
+                }
+            },
+            // toplevel strings are comments
+            Some(&b'"') => {
+                match klstring(input) {
+                    IResult::Done(rest, _) => input = rest,
+                    _ => return Err(ParseError::new(kl, offset, string_error_message(input)))
+                }
+            },
+            Some(&b')') => return Err(ParseError::new(kl, offset, String::from("unmatched ')'"))),
+            Some(_) => return Err(ParseError::new(kl, offset, String::from("expecting '(' or a string at toplevel")))
+        }
+    }
+}
+
+pub fn parse_kl(kl: &[u8]) -> Result<Vec<KlToken>, ParseError> {
+    parse_kl_forms(kl).map(| forms | forms.into_iter().map(| (_, form) | form).collect())
+}
+// Parse\ Errors:1 ends here
+
+// [[file:../shen-rust.org::*Source%20Spans][Source\ Spans:1]]
+// Turns the byte offsets the parser records for each form, and those of
+// the subforms within it, into lines and columns.
+struct KlSourceIndex<'a> {
+    kl: &'a [u8],
+    file: Option<Rc<String>>,
+    line_starts: Vec<usize>,
+    first_line: usize
+}
+
+impl<'a> KlSourceIndex<'a> {
+    fn new(kl: &'a [u8], file: Option<&str>, first_line: usize) -> KlSourceIndex<'a> {
+        let mut line_starts = vec![0];
+        line_starts.extend(kl.iter().enumerate().filter(| &(_, &b) | b == b'\n').map(| (i, _) | i + 1));
+        KlSourceIndex { kl: kl, file: file.map(| f | Rc::new(String::from(f))), line_starts: line_starts, first_line: first_line }
+    }
+
+    fn span_at(&self, offset: usize) -> KlSpan {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(l) => l,
+            Err(l) => l - 1
+        };
+        let column = String::from_utf8_lossy(&self.kl[self.line_starts[line]..offset]).chars().count();
+        KlSpan { file: self.file.clone(), line: self.first_line + line + 1, column: column + 1 }
+    }
+
+    // Steps over token, which the parser read starting at offset, the way
+    // klsexp did, noting where each of its subforms starts.
+    fn span_tree(&self, offset: &mut usize, token: &KlToken) -> KlSpanTree {
+        *offset = skip_whitespace_and_comments(self.kl, *offset).unwrap_or(self.kl.len());
+        let span = self.span_at(*offset);
+        let rest = &self.kl[*offset..];
+        let children = match token {
+            &KlToken::Cons(ref elements) => {
+                *offset += 1;
+                let children = elements.iter().map(| e | Rc::new(self.span_tree(offset, e))).collect();
+                *offset = skip_whitespace_and_comments(self.kl, *offset).unwrap_or(self.kl.len()) + 1;
+                children
+            },
+            _ => {
+                if let IResult::Done(after, _) = klatom(rest) {
+                    *offset += rest.len() - after.len();
+                }
+                Vec::new()
+            }
+        };
+        KlSpanTree { span: span, children: children }
+    }
+}
+
+pub fn parse_kl_with_spans(kl: &[u8], file: Option<&str>) -> Result<Vec<(KlToken, KlSpanTree)>, ParseError> {
+    let forms = parse_kl_forms(kl).map_err(| e | match file { Some(f) => e.in_file(f), None => e })?;
+    let index = KlSourceIndex::new(kl, file, 0);
+    Ok(forms.into_iter().map(| (mut offset, form) | {
+        let spans = index.span_tree(&mut offset, &form);
+        (form, spans)
+    }).collect())
+}
+
+thread_local!(static DEFUN_SPAN_TABLE: RefCell<HashMap<KlSymbol, KlSpan>> = RefCell::new(HashMap::new()));
+
+pub fn shen_record_defun_span(name: KlSymbol, span: KlSpan) {
+    DEFUN_SPAN_TABLE.with(| table | {
+        table.borrow_mut().insert(name, span);
+    })
+}
+
+pub fn shen_lookup_defun_span(name: KlSymbol) -> Option<KlSpan> {
+    DEFUN_SPAN_TABLE.with(| table | table.borrow().get(&name).cloned())
+}
+
+// Notes that error came out of the form at span, unless it already has a
+// site in the function it is unwinding through.
+pub fn shen_error_at(error: Rc<KlError>, span: &KlSpan) -> Rc<KlError> {
+    if error.site.is_some() {
+        return error;
+    }
+    let mut error = error;
+    Rc::make_mut(&mut error).site = Some(span.clone());
+    error
+}
+
+// shen_error_at for compiled code, which knows its spans as literals.
+pub fn shen_closure_at(c: KlClosure, file: Option<&str>, line: usize, column: usize) -> KlClosure {
+    match c {
+        KlClosure::Done(Err(ref e)) if e.site.is_none() =>
+            KlClosure::Done(Err(shen_error_at(e.clone(), &KlSpan::new(file, line, column)))),
+        c => c
+    }
+}
+
+pub fn shen_element_at(e: Rc<KlElement>, file: Option<&str>, line: usize, column: usize) -> Rc<KlElement> {
+    match &*e {
+        &KlElement::Closure(ref c @ KlClosure::Done(Err(_))) =>
+            Rc::new(KlElement::Closure(shen_closure_at(c.clone(), file, line, column))),
+        _ => e.clone()
+    }
+}
+
+// Records that error unwound through function, from the site it last came
+// out of. Primitives are left out: the one that raised the error is
+// already its origin.
+pub fn shen_trace_error(function: KlSymbol, error: Rc<KlError>) -> Rc<KlError> {
+    if shen_is_primitive(function) {
+        return error;
+    }
+    let mut error = error;
+    {
+        let traced = Rc::make_mut(&mut error);
+        let site = traced.site.take();
+        if traced.trace.len() < ERROR_TRACE_LIMIT {
+            traced.trace.push(KlTraceEntry { function: function, site: site });
+        }
+        else {
+            traced.trace_omitted += 1;
+        }
+    }
+    error
+}
+
+// A closure has no entry in the trace, so an error that comes out of one
+// drops the site it had there: that site is in the function the closure
+// was written in, not the one that called it.
+pub fn shen_error_left_closure(error: Rc<KlError>) -> Rc<KlError> {
+    if error.site.is_none() {
+        return error;
+    }
+    let mut error = error;
+    Rc::make_mut(&mut error).site = None;
+    error
+}
+
+// One line per traced function, each starting with a newline, so that the
+// trace can be printed straight after the message. A function is located
+// by the site the error left it from, or failing that by its defun.
+pub fn shen_format_error_trace(error: &KlError) -> String {
+    let mut lines = String::new();
+    for entry in error.trace.iter() {
+        match entry.site.clone().or_else(|| shen_lookup_defun_span(entry.function)) {
+            Some(span) => lines.push_str(&format!("\n    in {} ({})", shen_symbol_name(entry.function), span)),
+            None => lines.push_str(&format!("\n    in {}", shen_symbol_name(entry.function)))
+        }
+    }
+    if error.trace_omitted > 0 {
+        lines.push_str(&format!("\n    ... and {} more", error.trace_omitted));
+    }
+    lines
+}
+// Source\ Spans:1 ends here
+
+// [[file:../shen-rust.org::*Reader][Reader:1]]
+pub struct KlReader {
+    buffer: Vec<u8>,
+    position: usize,
+    first_line: usize,
+    first_offset: usize,
+    file: Option<String>,
+    closed: bool
+}
+
+impl KlReader {
+    pub fn new() -> KlReader {
+        KlReader {
+            buffer: Vec::new(),
+            position: 0,
+            first_line: 0,
+            first_offset: 0,
+            file: None,
+            closed: false
+        }
+    }
+
+    pub fn for_file(file: &str) -> KlReader {
+        KlReader { file: Some(String::from(file)), .. KlReader::new() }
+    }
+
+    pub fn feed(&mut self, input: &[u8]) {
+        self.buffer.extend_from_slice(input);
+    }
+
+    // No more input is coming, so a form left open is now an error.
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    // True when the buffered input starts a form that hasn't closed yet.
+    pub fn needs_more_input(&self) -> bool {
+        match skip_whitespace_and_comments(&self.buffer, self.position) {
+            Ok(i) => !self.closed && i < self.buffer.len(),
+            Err(_) => !self.closed
+        }
+    }
+
+    pub fn next_form(&mut self) -> Result<Option<KlToken>, ParseError> {
+        self.next_form_with_spans().map(| form | form.map(| (token, _) | token))
+    }
+
+    // Ok(None) means every complete form has been read: feed more input,
+    // or if the reader is closed, the input is exhausted.
+    pub fn next_form_with_spans(&mut self) -> Result<Option<(KlToken, KlSpanTree)>, ParseError> {
+        loop {
+            let start = match skip_whitespace_and_comments(&self.buffer, self.position) {
+                Ok(start) => start,
+                Err(comment) if self.closed => {
+                    self.position = self.buffer.len();
+                    return Err(self.error_at(comment, String::from("unterminated block comment")))
+                },
+                Err(_) => return Ok(None)
+            };
+            if start == self.buffer.len() {
+                self.position = start;
+                self.discard_consumed();
+                return Ok(None)
+            }
+            let end = match form_extent(&self.buffer, start) {
+                FormExtent::Complete(end) | FormExtent::TooDeep { end: Some(end), .. } => end,
+                _ if self.closed => self.buffer.len(),
+                _ => return Ok(None)
+            };
+            self.position = end;
+            let parsed = parse_kl_forms(&self.buffer[start..end])
+                .map_err(| e | self.error_at(start + e.offset, e.message))?;
+            match parsed.into_iter().next() {
+                Some((offset, token)) => {
+                    let spans = {
+                        let index = KlSourceIndex::new(&self.buffer, self.file.as_ref().map(| f | f.as_str()), self.first_line);
+                        index.span_tree(&mut (start + offset), &token)
+                    };
+                    self.discard_consumed();
+                    return Ok(Some((token, spans)))
+                },
+                // toplevel strings are comments
+                None => ()
+            }
+        }
+    }
+
+    fn error_at(&self, offset: usize, message: String) -> ParseError {
+        let error = ParseError::new(&self.buffer, offset, message);
+        let error = ParseError {
+            offset: self.first_offset + error.offset,
+            line: self.first_line + error.line,
+            .. error
+        };
+        match self.file {
+            Some(ref f) => error.in_file(f),
+            None => error
+        }
+    }
+
+    // Drops whole lines that have been read, keeping line and column
+    // numbers of later errors and spans relative to the full input.
+    fn discard_consumed(&mut self) {
+        match self.buffer[..self.position].iter().rposition(| &b | b == b'\n') {
+            Some(newline) => {
+                let cut = newline + 1;
+                self.first_line += self.buffer[..cut].iter().filter(| &&b | b == b'\n').count();
+                self.first_offset += cut;
+                self.buffer.drain(..cut);
+                self.position -= cut;
+            },
+            None => ()
+        }
+    }
+}
+// Reader:1 ends here
+
+// [[file:../shen-rust.org::*Printer][Printer:1]]
+fn is_character_code(s: &str) -> bool {
+    let digits : String = s.chars().skip(1).take_while(| c | c.is_digit(10)).collect();
+    s.starts_with("#") && digits.len() > 0 && s[1 + digits.len() ..].starts_with(";")
+}
+
+pub fn print_kl_string(s: &String) -> String {
+    let mut printed = String::from("\"");
+    for (i, c) in s.char_indices() {
+        let rest = &s[i + c.len_utf8() ..];
+        match c {
+            '"' => printed.push_str("\\\""),
+            '\\' => printed.push_str("\\\\"),
+            '\n' => printed.push_str("\\n"),
+            '\t' => printed.push_str("\\t"),
+            '\r' => printed.push_str("\\r"),
+            // keep a literal "c#NN;" from being read back as a character code
+            'c' if is_character_code(&rest) => printed.push_str("\\u0063"),
+            _ if c.is_control() && (c as u32) <= 0xFFFF => printed.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => printed.push(c)
+        }
+    }
+    printed.push('"');
+    printed
+}
+
+pub fn print_kl_number(n: &KlNumber) -> String {
+    match n {
+        &KlNumber::Int(i) => format!("{}", i),
+        &KlNumber::Big(ref b) => format!("{}", b),
+        &KlNumber::Float(f) => {
+            let magnitude = f.abs();
+            if f.is_finite() && magnitude != 0.0 && (magnitude >= 1e16 || magnitude < 1e-5) {
+                let printed = format!("{:e}", f);
+                match printed.find('e') {
+                    Some(i) if !printed[.. i].contains('.') => format!("{}.0{}", &printed[.. i], &printed[i ..]),
+                    _ => printed
+                }
+            }
+            else {
+                let printed = format!("{}", f);
+                if f.is_finite() && !printed.contains('.') {
+                    printed + ".0"
+                }
+                else {
+                    printed
+                }
+            }
+        }
+    }
+}
+
+fn print_kl_list(items: Vec<String>) -> String {
+    format!("({})", intersperse(items, String::from(" ")))
+}
+
+impl fmt::Display for KlToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &KlToken::Symbol(s) => write!(f, "{}", shen_symbol_name(s)),
+            &KlToken::Number(ref n) => write!(f, "{}", print_kl_number(n)),
+            &KlToken::String(ref s) => write!(f, "{}", print_kl_string(s)),
+            &KlToken::Cons(ref inner) => write!(f, "{}", print_kl_list(inner.iter().map(| t | format!("{}", t)).collect())),
+            // A self-call the compiler turned into a loop has no KLambda
+            // syntax, so it prints as an unreadable object.
+            &KlToken::Recur(ref args) => {
+                let mut items = vec![String::from("<recur")];
+                items.extend(args.iter().map(| t | format!("{}", t)));
+                write!(f, "{}>", intersperse(items, String::from(" ")))
+            }
+        }
+    }
+}
+
+// The vectors being printed, outermost first. A vector that holds itself,
+// directly or through others, prints as <...> where it recurs.
+thread_local!(static PRINTING_VECTORS: RefCell<Vec<*const UniqueVector>> = RefCell::new(Vec::new()));
+
+impl fmt::Display for KlElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Elements nested too deeply to print in the stack that is left are
+        // elided.
+        if shen_stack_exhausted() {
+            return write!(f, "...")
+        }
+        match self {
+            &KlElement::Symbol(s) => write!(f, "{}", shen_symbol_name(s)),
+            &KlElement::Number(ref n) => write!(f, "{}", print_kl_number(n)),
+            &KlElement::String(ref s) => write!(f, "{}", print_kl_string(s)),
+            &KlElement::Cons(_, _) => {
+                let mut items = Vec::new();
+                let mut current = self;
+                while let &KlElement::Cons(ref head, ref tail) = current {
+                    items.push(format!("{}", head));
+                    current = &**tail;
+                }
+                match current {
+                    &KlElement::Nil => (),
+                    improper_tail => {
+                        items.push(String::from("|"));
+                        items.push(format!("{}", improper_tail));
+                    }
+                }
+                write!(f, "{}", print_kl_list(items))
+            },
+            &KlElement::Nil => write!(f, "()"),
+            &KlElement::Closure(KlClosure::Done(Ok(Some(ref e)))) => write!(f, "{}", e),
+            &KlElement::Closure(KlClosure::Done(Ok(None))) => write!(f, "<no value>"),
+            &KlElement::Closure(KlClosure::Done(Err(ref e))) => write!(f, "<error: {}>", e.message()),
+            &KlElement::Closure(_) => write!(f, "<closure>"),
+            &KlElement::Vector(ref v) => {
+                let address : *const UniqueVector = &**v;
+                if PRINTING_VECTORS.with(| printing | printing.borrow().contains(&address)) {
+                    return write!(f, "<...>")
+                }
+                PRINTING_VECTORS.with(| printing | printing.borrow_mut().push(address));
+                let mut items = vec![String::from("<absvector")];
+                items.extend(v.vector.borrow().iter().map(| e | format!("{}", e)));
+                PRINTING_VECTORS.with(| printing | printing.borrow_mut().pop());
+                write!(f, "{}>", intersperse(items, String::from(" ")))
+            },
+            &KlElement::Stream(ref s) => {
+                match &**s {
+                    &KlStream::FileStream(_) => write!(f, "<file stream>"),
+                    &KlStream::Std(KlStdStream::Stdout) => write!(f, "<stdout>"),
+                    &KlStream::Std(KlStdStream::Stdin) => write!(f, "<stdin>")
+                }
+            },
+            &KlElement::Recur(ref args) => {
+                let mut items = vec![String::from("<recur")];
+                items.extend(args.iter().map(| e | format!("{}", e)));
+                write!(f, "{}>", intersperse(items, String::from(" ")))
+            },
+            &KlElement::Exception(ref e) => write!(f, "<exception: {}>", e.message())
+        }
+    }
+}
+// Printer:1 ends here
+
+// [[file:../shen-rust.org::*Path%20Utilites][Path\ Utilites:1]]
+pub fn add_path (old_path:&Vec<usize>, new_path:Vec<usize>) -> Vec<usize> {
+    let mut p = old_path.clone();
+    p.extend(new_path);
+    p
+}
+// Path\ Utilites:1 ends here
+
+// [[file:../shen-rust.org::*Getter][Getter:1]]
+pub fn get_element_at (path : Vec<usize>, sexp: &KlToken)  -> Option<&KlToken> {
+    let mut current_token = sexp;
+    for index in path {
+        if let &KlToken::Cons(ref current) = current_token {
+            if index < current.len() {
+                current_token = &current[index];
+            }
+            else {
+                return None;
+            }
+        }
+        else {
+            return None;
+        }
+    }
+    Some(current_token)
+}
+// Getter:1 ends here
+
+// [[file:../shen-rust.org::*Setter][Setter:1]]
+pub fn set_element_at(mut path : Vec<usize>, sexp: &mut KlToken, token: KlToken) -> () {
+    match (path.pop(), sexp) {
+        (Some(p), &mut KlToken::Cons(ref mut vec)) => {
+            set_element_at(path, &mut vec[p], token)
+        }
+        (None, ref mut val) => {
+            **val = token;
+        }
+        _ => panic!("Gah!")
+    }
+}
+// Setter:1 ends here
+
+// [[file:../shen-rust.org::*Mark%20Recur][Mark\ Recur:1]]
+// Also drops the span of the function from spans, if they are known, so
+// that they stay those of the Recur's arguments.
+pub fn mark_recur(mut path: Vec<usize>, sexp: &mut KlToken, spans: Option<&mut Rc<KlSpanTree>>) -> () {
+    match (path.pop(), sexp) {
+        (Some(p), &mut KlToken::Cons(ref mut vec)) => {
+            mark_recur(path, &mut vec[p], spans.and_then(| s | Rc::make_mut(s).children.get_mut(p)))
+        }
+        (None, ref mut val) => {
+            match val.clone() {
+                KlToken::Cons(ref vec) => {
+                    let mut new_vec = vec.clone();
+                    new_vec.reverse();
+                    new_vec.pop();
+                    new_vec.reverse();
+                    **val = KlToken::Recur(new_vec);
+                    if let Some(s) = spans {
+                        if !s.children.is_empty() {
+                            Rc::make_mut(s).children.remove(0);
+                        }
+                    }
+                }
+                _ => panic!("Gah!")
+            }
+        },
+        _ => panic!("Gah!")
+    }
+}
+// Mark\ Recur:1 ends here
+
+// [[file:../shen-rust.org::*Tail%20Contexts][Tail\ Contexts:1]]
+// The places in a form whose value is the value of the whole form, as paths
+// into it:
+//
+//   (defun F Args Body)              Body
+//   (if Test Then Else)              Then, Else
+//   (cond (Test Action) ...)         every Action
+//   (let X Value Body)               Body
+//   (do Form ... Last)               Last
+//   (trap-error Form (lambda E H))   H
+//
+// Nothing else is a tail context. That covers the arguments of any other
+// application, including the operands of and/or, whose values are checked
+// to be booleans, and the protected form of trap-error, which has to finish
+// while the handler is still in place. It also covers the bodies of lambda
+// and freeze, which run later as calls of their own.
+pub fn tail_contexts(sexp: &KlToken) -> Vec<Vec<usize>> {
+    let mut contexts = Vec::new();
+    if let &KlToken::Cons(ref form) = sexp {
+        if let Some(&KlToken::Symbol(s)) = form.first() {
+            match form.as_slice() {
+                &[_, _, _, _] if s == KlSymbol::DEFUN || s == KlSymbol::LET => contexts.push(vec![3]),
+                &[_, _, _, _] if s == KlSymbol::IF => {
+                    contexts.push(vec![2]);
+                    contexts.push(vec![3]);
+                },
+                &[_, ref cases @ ..] if s == KlSymbol::COND => {
+                    for (index, case) in cases.iter().enumerate() {
+                        if let &KlToken::Cons(ref pair) = case {
+                            if pair.len() == 2 {
+                                contexts.push(vec![index + 1, 1]);
+                            }
+                        }
+                    }
+                },
+                &[_, _, ..] if s == KlSymbol::DO => contexts.push(vec![form.len() - 1]),
+                &[_, _, KlToken::Cons(ref handler)] if s == KlSymbol::TRAP_ERROR => {
+                    match handler.as_slice() {
+                        &[KlToken::Symbol(lambda), _, _] if lambda == KlSymbol::LAMBDA =>
+                            contexts.push(vec![2, 2]),
+                        _ => ()
+                    }
+                },
+                _ => ()
+            }
+        }
+    }
+    contexts
+}
+
+// The variable a tail context of sexp is evaluated under, if any.
+pub fn tail_context_binding(sexp: &KlToken) -> Option<KlSymbol> {
+    if let &KlToken::Cons(ref form) = sexp {
+        match form.as_slice() {
+            &[KlToken::Symbol(s), KlToken::Symbol(x), _, _] if s == KlSymbol::LET => return Some(x),
+            &[KlToken::Symbol(s), _, KlToken::Cons(ref handler)] if s == KlSymbol::TRAP_ERROR => {
+                if let &[_, KlToken::Symbol(e), _] = handler.as_slice() {
+                    return Some(e);
+                }
+            },
+            _ => ()
+        }
+    }
+    None
+}
+// Tail\ Contexts:1 ends here
+
+// [[file:../shen-rust.org::*Detect%20Possible%20Recursive%20Calls][Detect\ Possible\ Recursive\ Calls:1]]
+// Paths to the calls of function_name with num_args arguments that sit in
+// tail contexts of sexp, following tail contexts down as far as they go. A
+// variable named function_name hides the function from there on.
+pub fn find_recursive_calls (function_name: KlSymbol, num_args: usize, sexp: &KlToken) -> Vec<Vec<usize>> {
+    let mut found : Vec< Vec<usize> >= Vec::new();
+    let mut pending : Vec <(Vec<usize>, &KlToken)> = vec![(Vec::new(), sexp)];
+    while pending.len() > 0 {
+        let (path, current) = pending.pop().unwrap();
+        if let &KlToken::Cons(ref form) = current {
+            match form.as_slice() {
+                &[KlToken::Symbol(s), ref rest @ ..] if s == function_name && rest.len() == num_args => {
+                    found.push(path.clone());
+                    continue;
+                },
+                _ => ()
+            }
+        }
+        if tail_context_binding(current) == Some(function_name) {
+            continue;
+        }
+        let mut newly_found = Vec::new();
+        for context in tail_contexts(current) {
+            if let Some(tail) = get_element_at(context.clone(), current) {
+                newly_found.push((add_path(&path, context), tail));
+            }
+        }
+        newly_found.reverse();
+        pending.extend(newly_found);
+    }
+    found
+}
+// Detect\ Possible\ Recursive\ Calls:1 ends here
+
+// [[file:../shen-rust.org::*Get%20Tail%20Calls][Get\ Tail\ Calls:1]]
+pub fn shen_get_all_tail_calls (sexp: &KlToken) -> Vec<Vec<usize>> {
+    if let &KlToken::Cons(ref defun) = sexp {
+        match defun.as_slice() {
+            &[KlToken::Symbol(ref defun), KlToken::Symbol(ref name), KlToken::Cons(ref args), _]
+                if *defun == KlSymbol::DEFUN => {
+                    if args.iter().any(| a | a == &KlToken::Symbol(*name)) {
+                        Vec::new()
+                    }
+                    else {
+                        find_recursive_calls(*name, args.len(), sexp)
+                    }
+                },
+            _ => Vec::new()
+        }
+    }
+    else {
+        Vec::new()
+    }
+}
+// Get\ Tail\ Calls:1 ends here
+
+// [[file:../shen-rust.org::*Intersperse][Intersperse:1]]
+pub fn intersperse(v: Vec<String>, sep: String) -> String {
+    if v.len() == 0 {
+        String::new()
+    }
+    else {
+        let mut so_far = String::new();
+        for i in 0..v.len() {
+            so_far = so_far + &(v[i].clone());
+            if i != v.len() - 1 {
+                so_far = so_far + &sep.clone();
+            }
+        }
+        so_far
+    }
+}
+// Intersperse:1 ends here
+
+// [[file:../shen-rust.org::*Function%20Lookup][Function\ Lookup:1]]
+pub fn shen_lookup_function(s: KlSymbol) -> Option<KlClosure> {
+    FUNCTION_TABLE.with(|table|{
+        let table = table.borrow();
+        let function = table.get(&s);
+        match function {
+            Some(f) => Some((*f).clone()),
+            None => None
+        }
+    })
+}
+// Function\ Lookup:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+pub fn shen_apply_arguments_to_lambda(l: KlClosure, a: Rc<KlElement>) -> Result<KlClosure, String> {
+    match l {
+        KlClosure::FeedMe(ref f) => {
+            let result = (&f)(a);
+            match &result {
+                &KlClosure::Done(_) | &KlClosure::Trampoline(_) => Ok(result.clone()),
+                _ => Err(String::from("Expecting an unsaturated closure."))
+            }
+        }
+        _ => Err(String::from("Expecting an unsaturated closure."))
+    }
+}
+
+// An argument that failed fails the call before anything is applied, and
+// before the function it was meant for shows up in the error's trace.
+pub fn shen_failed_argument(elements: &Vec<Rc<KlElement>>) -> Option<Rc<KlError>> {
+    for e in elements.iter() {
+        if let &KlElement::Closure(KlClosure::Done(Err(ref error))) = &**e {
+            return Some(error.clone());
+        }
+    }
+    None
+}
+
+pub fn shen_apply_arguments_to_function(s: KlSymbol, elements: Vec<Rc<KlElement>>) -> Result<KlClosure, String> {
+    if let Some(error) = shen_failed_argument(&elements) {
+        return Ok(KlClosure::Done(Err(error)));
+    }
+    match shen_lookup_function(s) {
+        Some(f) => {
+            match shen_apply_arguments(f.clone(), elements) {
+                Ok(KlClosure::Done(Err(e))) => Ok(KlClosure::Done(Err(shen_trace_error(s, e)))),
+                // The body of s has returned, but an error from the call it
+                // left pending still happened in s.
+                Ok(KlClosure::Trampoline(bounce)) if !shen_is_primitive(s) =>
+                    Ok(KlClosure::Trampoline(Rc::new(move || {
+                        let next = bounce();
+                        match &*next {
+                            &KlElement::Closure(KlClosure::Done(Err(ref e))) =>
+                                Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_trace_error(s, e.clone()))))),
+                            _ => next.clone()
+                        }
+                    }))),
+                applied => applied
+            }
+        },
+        None => Ok(KlClosure::Done(Err(Rc::new(KlError::new(KlErrorKind::Undefined, None, format!("Could not find function: {}", shen_symbol_name(s)))))))
+    }
+}
+
+pub fn shen_apply_arguments(c : KlClosure , elements: Vec<Rc<KlElement>>) -> Result<KlClosure, String> {
+    if shen_stack_exhausted() {
+        return Ok(KlClosure::Done(Err(shen_stack_overflow_error())));
+    }
+    if let Some(error) = shen_failed_argument(&elements) {
+        return Ok(KlClosure::Done(Err(error)));
+    }
+    match c {
+        KlClosure::FeedMe(_) => {
+            let mut so_far : KlClosure = c.clone();
+            for (i, e) in elements.iter().enumerate() {
+                match so_far {
+                    KlClosure::FeedMe(f) => so_far = (&f)((*e).clone()),
+                    KlClosure::Done(Ok(Some(_))) | KlClosure::Trampoline(_) =>
+                        return shen_apply_arguments(shen_run_trampoline(so_far.clone()), elements[i..].to_vec()),
+                    _ => break
+                }
+            }
+            Ok(so_far.clone())
+        },
+        KlClosure::Trampoline(_) if elements.len() > 0 => shen_apply_arguments(shen_run_trampoline(c), elements),
+        KlClosure::Done(Ok(Some(ref e))) if elements.len() > 0 => {
+            match &**e {
+                &KlElement::Closure(ref inner) => shen_apply_arguments(inner.clone(), elements),
+                _ => Err(String::from("Given a fully saturated closure or thunk"))
+            }
+        },
+        _ => {
+            if elements.len() == 0 {
+                Ok(shen_force_closure(c.clone()))
+            }
+            else {
+                Err(String::from("Given a fully saturated closure or thunk"))
+            }
+        }
+    }
+}
+
+pub fn shen_apply_element(c: Rc<KlElement>, elements: Vec<Rc<KlElement>>) -> Result<KlClosure, String> {
+    let applied = match &*c {
+        &KlElement::Closure(ref c) => {
+            if elements.len() == 0 {
+                Ok(shen_force_closure(c.clone()))
+            }
+            else {
+                shen_apply_arguments(c.clone(), elements)
+            }
+        },
+        _ => Err(String::from("Expecting closure."))
+    };
+    shen_left_closure(applied)
+}
+
+// Applies a closure got as a value, like the one a curried application
+// returns, rather than a function named by a symbol.
+pub fn shen_apply_closure(c: KlClosure, elements: Vec<Rc<KlElement>>) -> Result<KlClosure, String> {
+    shen_left_closure(shen_apply_arguments(c, elements))
+}
+
+// The result of applying a closure, with shen_error_left_closure done to
+// the error it ends in, now or once a call it left pending has run.
+pub fn shen_left_closure(applied: Result<KlClosure, String>) -> Result<KlClosure, String> {
+    match applied {
+        Ok(KlClosure::Done(Err(e))) => Ok(KlClosure::Done(Err(shen_error_left_closure(e)))),
+        Ok(KlClosure::Trampoline(bounce)) =>
+            Ok(KlClosure::Trampoline(Rc::new(move || {
+                let next = bounce();
+                match &*next {
+                    &KlElement::Closure(KlClosure::Done(Err(ref e))) =>
+                        Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_error_left_closure(e.clone()))))),
+                    _ => next.clone()
+                }
+            }))),
+        applied => applied
+    }
+}
+
+pub fn shen_element_to_closure(e : Rc<KlElement>) -> KlClosure {
+    match &*e {
+        &KlElement::Closure(KlClosure::Done(ref r)) => KlClosure::Done(r.clone()),
+        &KlElement::Closure(KlClosure::Trampoline(ref bounce)) => KlClosure::Trampoline(bounce.clone()),
+        _ => KlClosure::Done(Ok(Some(e.clone())))
+    }
+}
+
+// A call in tail position is not made where it appears: it comes back as a
+// Trampoline and whoever needs the value runs the pending calls here, one
+// after the other, so a chain of tail calls takes constant stack no matter
+// which functions or closures it passes through.
+pub fn shen_run_trampoline(c : KlClosure) -> KlClosure {
+    let mut c = c;
+    while let KlClosure::Trampoline(bounce) = c {
+        c = shen_element_to_closure(bounce());
+    }
+    c
+}
+
+pub fn shen_tail_call<F>(call: F) -> KlClosure where F: Fn() -> Result<KlClosure, String> + 'static {
+    KlClosure::Trampoline(Rc::new(move || {
+        match call() {
+            Ok(c) => shen_tail_closure_to_element(c),
+            Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+        }
+    }))
+}
+
+pub fn shen_force_closure(c : KlClosure) -> KlClosure {
+    match c {
+        KlClosure::Thunk(ref inner) => shen_element_to_closure(inner()),
+        _ => c.clone()
+    }
+}
+
+pub fn shen_closure_to_element(c : KlClosure) -> Rc<KlElement> {
+    shen_tail_closure_to_element(shen_run_trampoline(c))
+}
+
+// Like shen_closure_to_element, but a pending tail call stays pending.
+pub fn shen_tail_closure_to_element(c : KlClosure) -> Rc<KlElement> {
+    match c {
+        KlClosure::Done(Ok(Some(v))) => v.clone(),
+        KlClosure::Done(Ok(None)) => Rc::new(KlElement::Closure(KlClosure::Done(Err(Rc::new(KlError::new(KlErrorKind::NoValue, None, String::from("expected a value but got none."))))))),
+        _ => Rc::new(KlElement::Closure(c.clone()))
+    }
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*Application%20Generation][Application\ Generation:1]]
+// The arguments shen_closure_at and shen_element_at need to note that an
+// error came out of the form at span.
+pub fn generate_site(span: &KlSpan) -> String {
+    format!("{:?}, {}, {}", span.file.as_ref().map(| f | f.as_str()), span.line, span.column)
+}
+
+// The symbols the code generated so far names, so that generate_module can
+// look each one up once at the top of init instead of every time the code
+// runs.
+thread_local!(static GENERATED_SYMBOLS: RefCell<Vec<KlSymbol>> = RefCell::new(Vec::new()));
+
+// The variable generated code holds s in.
+pub fn generate_symbol_name(s: KlSymbol) -> String {
+    format!("symbol_{}", s.as_str())
+}
+
+// Refers to s in generated code, through the variable generate_module
+// declares for it.
+pub fn generate_symbol(s: KlSymbol) -> String {
+    GENERATED_SYMBOLS.with(| symbols | symbols.borrow_mut().push(s));
+    generate_symbol_name(s)
+}
+
+pub fn generate_apply(is_argument: bool, function_call: String, site: Option<&KlSpan>) -> Vec<String> {
+    let mut result = Vec::new();
+    result.push(format!("match {} {{", function_call));
+    match (is_argument, site) {
+        (true, None) =>
+            result.push(String::from("Ok(c) => shen_closure_to_element(c.clone()), \n Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))")),
+        (true, Some(span)) =>
+            result.push(format!("Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), {}), \n Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))", generate_site(span))),
+        (false, None) =>
+            result.push(String::from("Ok(c) => c.clone(), \n Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))")),
+        (false, Some(span)) =>
+            result.push(format!("Ok(c) => shen_closure_at(c.clone(), {}), \n Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))", generate_site(span)))
+    }
+    result.push(String::from("}"));
+    result
+}
+
+pub fn shen_apply_function(is_argument: bool, s: KlSymbol, args: Vec<String>, site: Option<&KlSpan>) -> Vec<String> {
+    let mut application = Vec::new();
+    application.push(format!("shen_apply_arguments_to_function({}, vec![", generate_symbol(s)));
+    application.push(intersperse(args,String::from(",")));
+    application.push(String::from("])"));
+    generate_apply(is_argument, intersperse(application, String::from("\n")), site)
+}
+
+pub fn shen_apply_arguments_to_curried(is_argument: bool, s: String, args: Vec<String>, site: Option<&KlSpan>) -> Vec<String> {
+    let mut application = Vec::new();
+    application.push(format!("shen_apply_closure({}, vec![", s));
+    application.push(intersperse(args,String::from(",")));
+    application.push(String::from("])"));
+    generate_apply(is_argument, intersperse(application, String::from("\n")), site)
+}
+
+pub fn shen_apply_argument(is_argument: bool, s: String, args: Vec<String>, site: Option<&KlSpan>) -> Vec<String> {
+    let mut application = Vec::new();
+    application.push(format!("shen_apply_element({}, vec![", s));
+    application.push(intersperse(args,String::from(",")));
+    application.push(String::from("])"));
+    generate_apply(is_argument, intersperse(application, String::from("\n")), site)
+}
+
+// The call is made each time the trampoline bounces, so the function is
+// cloned into it unless it is a KlSymbol, which is Copy.
+pub fn generate_tail_call(apply: &str, function: String, function_is_symbol: bool, args: Vec<String>, site: Option<&KlSpan>) -> Vec<String> {
+    let mut result = Vec::new();
+    let called = if function_is_symbol { "tail_function" } else { "tail_function.clone()" };
+    result.push(format!("{{ let tail_function = {};", function));
+    result.push(String::from("let tail_arguments = vec!["));
+    result.push(intersperse(args,String::from(",")));
+    result.push(String::from("];"));
+    result.push(String::from("match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>"));
+    match site {
+        Some(span) => result.push(format!("shen_tail_call(move || {}({}, tail_arguments.clone()).map(| c | shen_closure_at(c, {}))) }} }}",
+                                          apply, called, generate_site(span))),
+        None => result.push(format!("shen_tail_call(move || {}({}, tail_arguments.clone())) }} }}", apply, called))
+    }
+    result
+}
+
+pub fn shen_apply_lambda(is_argument: bool, l: String, arg: String) -> Vec<String> {
+    let application = format!("shen_apply_arguments_to_lambda({}, {})", l, arg);
+    generate_apply(is_argument, application, None)
+}
+
+pub fn generate_element(argument: bool, element: Vec<String>) -> Vec<String> {
+    if argument {
+        element
+    }
+    else {
+        let mut result = Vec::new();
+        result.push(String::from("KlClosure::Done(Ok(Some("));
+        result.extend(element);
+        result.push(String::from(")))"));
+        result
+    }
+}
+
+pub fn clone_bound_variables(bound: Vec<String>) -> String {
+    let clone_strings : Vec<String> = bound.iter().map(| v | format!("let {} = {}.clone()", v, v)).collect();
+    intersperse(clone_strings, String::from(";")) + ";"
+}
+
+pub fn generate_nested_closure(bound: Vec<String>, arg: String ) -> (Vec<String>, String) {
+    let mut result : Vec<String> = Vec::new();
+    let capture : Vec<String> = bound.iter().map(| b | format!("let {} = {}.clone();", b, b)).collect();
+    result.push(format!("{{ {} KlClosure::FeedMe(Rc::new(move |{}| {{", intersperse(capture, String::from("")), arg));
+    result.push(format!("let {}_Copy = (*{}).clone();", arg, arg));
+    for b in bound {
+        result.push(format!("let {}_Copy = (*{}).clone();", b, b));
+        result.push(format!("let {} = {}.clone();", b, b));
+    }
+    (result, String::from("})) }"))
+}
+// Application\ Generation:1 ends here
+
+// [[file:../shen-rust.org::*Thunk][Thunk:1]]
+pub fn generate_thunk(argument: bool, bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    let mut capture : Vec<String> = Vec::new();
+    for b in bound.clone() {
+        capture.push(format!("let {}_Copy = {}_Copy.clone();", b, b))
+    }
+    result.push(format!("Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( {{ {} move|| {{ ", intersperse(capture, String::from(""))));
+    for b in bound.clone() {
+        result.push(format!("let {} = Rc::new({}_Copy.clone());", b, b));
+        result.push(format!("let {}_Copy = (*{}).clone();", b ,b))
+    }
+    result.push(String::from("shen_tail_closure_to_element({"));
+    result.extend(generate(false,bound.clone(),token,spans)?);
+    result.push(String::from("})"));
+    result.push(String::from(" }}))))"));
+    Ok(generate_element(argument, result))
+}
+// Thunk:1 ends here
+
+// [[file:../shen-rust.org::*Lambda][Lambda:1]]
+pub fn generate_lambda(argument: bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klif) = &*token {
+        match klif.as_slice() {
+            &[KlToken::Symbol(ref kllambda), KlToken::Symbol(ref arg) , ref body] if *kllambda == KlSymbol::LAMBDA => {
+                let arg = &arg.as_str().to_string();
+                let mut new_bound = bound;
+                let (closures, closing) = {
+                    new_bound.retain(| x | x != arg);
+                    generate_nested_closure(new_bound.clone(), arg.clone())
+                };
+                new_bound.push(arg.clone());
+                if argument {
+                    result.push(String::from("Rc::new(KlElement::Closure(\n"));
+                }
+                result.push(intersperse(closures, String::from("\n")));
+                match body {
+                    &KlToken::Symbol(ref s) if new_bound.iter().any(| b | b.as_str() == &*s.as_str()) =>
+                        result.push(format!("KlClosure::Done(Ok(Some(Rc::new({}_Copy.clone()))))", s.as_str())),
+                    _ => result.extend(generate(false, new_bound.clone(), body, shen_spans_at(spans, &[2]))?),
+                }
+                result.push(closing);
+                if argument {
+                    result.push(String::from("))"))
+                }
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Lambda:1 ends here
+
+// [[file:../shen-rust.org::*Let][Let:1]]
+pub fn generate_let(argument: bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klif) = &*token {
+        match klif.as_slice() {
+            &[KlToken::Symbol(ref kllet), ref x @ KlToken::Symbol(_), ref y, ref body] if *kllet == KlSymbol::LET => {
+                let lambda_token = KlToken::Cons(vec![KlToken::Symbol(KlSymbol::LAMBDA), x.clone(), body.clone()]);
+                let lambda_spans = shen_spans_from(spans, &[0, 1, 3]);
+                let lambda_string = intersperse(generate_lambda(false, bound.clone(),&lambda_token,lambda_spans.as_ref())?, String::from("\n"));
+                let args_string = intersperse(generate(true, bound.clone(),y,shen_spans_at(spans, &[2]))?,String::from("\n"));
+                result = shen_apply_lambda(argument,lambda_string,args_string);
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Let:1 ends here
+
+// [[file:../shen-rust.org::*Cond][Cond:1]]
+pub fn generate_cond(argument:bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klcond) = &*token {
+        match klcond.as_slice() {
+            &[KlToken::Symbol(ref klcond), ref cases @ ..] if *klcond == KlSymbol::COND => {
+                let mut pairs = Vec::new();
+                let mut pair_list = Vec::new();
+                for (i, pair_cons) in cases.iter().enumerate() {
+                    match pair_cons {
+                        &KlToken::Cons(ref pair) => {
+                            match pair.as_slice() {
+                                &[ref predicate, ref action] => {
+                                    let predicate = intersperse(generate_thunk(true,bound.clone(),predicate,shen_spans_at(spans, &[i + 1, 0]))?,String::from("\n"));
+                                    let action = intersperse(generate_thunk(true,bound.clone(),action,shen_spans_at(spans, &[i + 1, 1]))?,String::from("\n"));
+                                    pairs.push(format!("shen_list(vec![{},{}])", predicate, action))
+                                },
+                                _ => ()
+                            }
+                        }
+                        _ => ()
+                    }
+                }
+                pair_list.push(String::from("shen_list(vec!["));
+                pair_list.push(intersperse(pairs,String::from(",")));
+                pair_list.push(String::from("])"));
+                result = shen_apply_function(argument, *klcond, vec![intersperse(pair_list,String::from("\n"))], spans.map(| s | &s.span));
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Cond:1 ends here
+
+// [[file:../shen-rust.org::*Freeze][Freeze:1]]
+pub fn generate_freeze(argument: bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klif) = &*token {
+        match klif.as_slice() {
+            &[KlToken::Symbol(ref klfreeze), ref a] if *klfreeze == KlSymbol::FREEZE=> {
+                result = generate_thunk(argument,bound.clone(),a,shen_spans_at(spans, &[1]))?;
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Freeze:1 ends here
+
+// [[file:../shen-rust.org::*And/Or][And/Or:1]]
+pub fn generate_and_or(argument: bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klif) = &*token {
+        match klif.as_slice() {
+            &[KlToken::Symbol(ref kland_or), ref a, ref b] if *kland_or == KlSymbol::AND || *kland_or == KlSymbol::OR => {
+                result = shen_apply_function(argument, *kland_or, vec![
+                    intersperse(generate_thunk(true,bound.clone(),a,shen_spans_at(spans, &[1]))?,String::from("\n")),
+                    intersperse(generate_thunk(true,bound.clone(),b,shen_spans_at(spans, &[2]))?,String::from("\n"))], spans.map(| s | &s.span));
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// And/Or:1 ends here
+
+// [[file:../shen-rust.org::*Trap%20Error][Trap\ Error:1]]
+pub fn generate_trap_error(argument: bool, bound: Vec<String>, token:&KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref kltrap) = &*token {
+        match kltrap.as_slice() {
+            &[KlToken::Symbol(ref kltrap), ref to_try, ref handler] if *kltrap == KlSymbol::TRAP_ERROR => {
+                result = shen_apply_function(argument, *kltrap, vec![
+                    intersperse(generate_thunk(true,bound.clone(),to_try,shen_spans_at(spans, &[1]))?,String::from("\n")),
+                    intersperse(generate(true,bound.clone(),handler,shen_spans_at(spans, &[2]))?,String::from("\n"))], spans.map(| s | &s.span));
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Trap\ Error:1 ends here
+
+// [[file:../shen-rust.org::*Do][Do:1]]
+pub fn generate_do(argument: bool, bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref kldo) = &*token {
+        match kldo.as_slice() {
+            &[KlToken::Symbol(ref kldo), ref forms @ .., ref last] if *kldo == KlSymbol::DO => {
+                let mut closings = Vec::new();
+                for (i, f) in forms.iter().enumerate() {
+                    result.push(String::from("{ let do_value = "));
+                    result.extend(generate(true, bound.clone(), f, shen_spans_at(spans, &[i + 1]))?);
+                    result.push(String::from(";"));
+                    result.push(String::from("match &*do_value {"));
+                    if argument {
+                        result.push(String::from("&KlElement::Closure(KlClosure::Done(Err(_))) => do_value.clone(),"));
+                    }
+                    else {
+                        result.push(String::from("&KlElement::Closure(KlClosure::Done(Err(ref e))) => KlClosure::Done(Err(e.clone())),"));
+                    }
+                    result.push(String::from("_ => "));
+                    closings.push(String::from("} }"));
+                }
+                result.extend(generate(argument, bound.clone(), last, shen_spans_at(spans, &[forms.len() + 1]))?);
+                result.extend(closings);
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Do:1 ends here
+
+// [[file:../shen-rust.org::*If][If:1]]
+pub fn generate_if(argument: bool, bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref klif) = &*token {
+        match klif.as_slice() {
+            &[KlToken::Symbol(ref klif), ref predicate, ref if_branch, ref else_branch] if *klif == KlSymbol::IF => {
+                result = shen_apply_function(argument, *klif, vec![
+                    intersperse(generate(true, bound.clone(),predicate,shen_spans_at(spans, &[1]))?,String::from("\n")),
+                    intersperse(generate_thunk(true,bound.clone(),if_branch,shen_spans_at(spans, &[2]))?,String::from("\n")),
+                    intersperse(generate_thunk(true,bound.clone(),else_branch,shen_spans_at(spans, &[3]))?,String::from("\n"))
+                ], spans.map(| s | &s.span));
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// If:1 ends here
+
+// [[file:../shen-rust.org::*Defun][Defun:1]]
+pub fn add_to_function_table(name: KlSymbol, c : KlClosure) {
+    FUNCTION_TABLE.with(| function_table | {
+        let mut map = function_table.borrow_mut();
+        map.insert(name, c);
+    });
+}
+
+pub fn extract_arg_names(args: Vec<KlToken>) -> Vec<String> {
+    args.as_slice().iter().filter_map(
+        | arg | {
+            match arg {
+                &KlToken::Symbol(s) => Some(s.as_str().to_string()),
+                _ => None
+            }
+        }
+    ).collect()
+}
+
+pub fn generate_defun(bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    if let &KlToken::Cons(ref kldefun) = &*token {
+        match kldefun.as_slice() {
+            &[KlToken::Symbol(ref kldefun), KlToken::Symbol(ref name), KlToken::Cons(ref args), ref body] if *kldefun == KlSymbol::DEFUN => {
+                let mut new_bound = bound.clone();
+                new_bound.extend(extract_arg_names(args.clone()));
+                result.push(String::from("{"));
+                result.push(String::from("let temp = "));
+                let arg_names = extract_arg_names(args.clone());
+                let mut closures = Vec::new();
+                let mut arguments_bound = Vec::new();
+                let mut closings = Vec::new();
+                if arg_names.len() == 0 {
+                    closures.push(String::from("KlClosure::Thunk(Rc::new(move || shen_tail_closure_to_element("));
+                    closings.push(String::from(")))"));
+                }
+                for a in arg_names.clone() {
+                    let (start, closing) = generate_nested_closure(arguments_bound.clone(), a.clone());
+                    arguments_bound.push(a);
+                    closures.extend(start);
+                    closings.push(closing);
+                }
+                result.extend(closures.clone());
+                let inner = generate(false, new_bound.clone(), body, shen_spans_at(spans, &[3]))?;
+                match &*body {
+                    &KlToken::Cons(_) => {
+                        let paths = shen_get_all_tail_calls(token);
+                        let mut token = token.clone();
+                        let mut marked_spans = spans.cloned();
+                        for p in paths.clone() {
+                            let mut p = p;
+                            p.reverse();
+                            mark_recur(p.clone(), &mut token, marked_spans.as_mut());
+                        }
+                        // println!("{:?}", token);
+                        if paths.len() > 0 {
+                            if let &KlToken::Cons(ref marked_defun) = &token {
+                                if let &[_,_,_,ref body] = marked_defun.as_slice() {
+                                    let inner = generate(false, new_bound.clone(), body, shen_spans_at(marked_spans.as_ref(), &[3]))?;
+                                    let mut trampoline = Vec::new();
+                                    trampoline.push(String::from("{"));
+                                    trampoline.push(String::from("let trampoline = | "));
+                                    trampoline.push(intersperse(arg_names.clone().iter().map(| a | format!("{} : Rc<KlElement>", a)).collect(), String::from(",")));
+                                    trampoline.push(String::from("| {"));
+                                    for a in arg_names.clone() {
+                                        trampoline.push(format!("let {}_Copy : KlElement = (*{}).clone();", a, a))
+                                    }
+                                    trampoline.extend(inner.clone());
+                                    trampoline.push(String::from("};"));
+                                    trampoline.push(format!("let mut done= None;"));
+                                    trampoline.push(
+                                        format!(
+                                            "let mut current_args = vec![{}];",
+                                            intersperse(
+                                                arg_names.clone().iter().map(| a | format!("{}.clone()", a)).collect(),
+                                                String::from(","))
+                                        )
+                                    );
+                                    trampoline.push(format!("while !done.is_some() {{"));
+                                    trampoline.push(
+                                        format!("let result = trampoline({});",
+                                                intersperse(arg_names.clone().iter().enumerate().map(| (i,_) | {
+                                                    format!("current_args[{}].clone()", i)
+                                                }).collect(),
+                                                            String::from(",")))
+                                    );
+                                    trampoline.push(
+                                        format!("let recur = match &result {{ &KlClosure::Done(Ok(Some(ref v))) => match &**v {{ &KlElement::Recur(ref v) => Some(v.clone()), _ => None }}, _ => None }};")
+                                    );
+                                    trampoline.push(
+                                        format!("match recur {{ Some(v) => match shen_failed_argument(&v) {{ Some(e) => done = Some(KlClosure::Done(Err(e))), None => current_args = v }}, None => done = Some(result.clone()) }};")
+                                    );
+                                    trampoline.push(String::from("}"));
+                                    trampoline.push(String::from("done.unwrap()"));
+                                    trampoline.push(String::from("}"));
+                                    result.push(intersperse(trampoline.clone(), String::from("\n")));
+                                }
+                            }
+                        }
+                        else {
+                            result.extend(inner.clone());
+                        }
+                    }
+                    _ => {
+                        result.extend(inner.clone());
+                    }
+                }
+                result.push(intersperse(closings.clone(), String::from("\n")));
+                result.push(String::from(";"));
+                result.push(format!("add_to_function_table({}, temp.clone())", generate_symbol(*name)));
+                result.push(String::from("}"));
+            },
+            _ => ()
+        }
+    }
+    Ok(result)
+}
+// Defun:1 ends here
+
+// [[file:../shen-rust.org::*Atoms][Atoms:1]]
+pub fn generate_atoms(argument: bool, bound: Vec<String>, token: &KlToken, _spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let element = match token {
+        &KlToken::Number(KlNumber::Int(i)) => vec![format!("Rc::new(KlElement::Number(KlNumber::Int({})))", i)],
+        &KlToken::Number(KlNumber::Float(i)) => vec![format!("Rc::new(KlElement::Number(KlNumber::Float({:?})))", i)],
+        &KlToken::Number(KlNumber::Big(ref b)) => vec![format!("Rc::new(KlElement::Number(shen_big_number({:?})))", b.to_string())],
+        &KlToken::String(ref s) => vec![format!("Rc::new(KlElement::String(String::from({:?})))", s)],
+        &KlToken::Symbol(ref s) => {
+            if bound.iter().any(| b | b.as_str() == &*s.as_str()) {
+                vec![format!("Rc::new({}_Copy.clone())", s.as_str())]
+            }
+            else {
+                vec![format!("Rc::new(KlElement::Symbol({}))", generate_symbol(*s))]
+            }
+        },
+        _ => return Ok(Vec::new())
+    };
+    Ok(generate_element(argument, element))
+}
+// Atoms:1 ends here
+
+// [[file:../shen-rust.org::*Application][Application:1]]
+// The arguments of an application, the first of which is element first of
+// the form spans belong to.
+pub fn generate_arguments(bound: &Vec<String>, args: &[KlToken], spans: Option<&Rc<KlSpanTree>>, first: usize) -> Result<Vec<String>, String> {
+    args.iter().enumerate().map(| (i, e) | generate(true, bound.clone(), e, shen_spans_at(spans, &[first + i])).map(| g | intersperse(g,String::from("\n")))).collect()
+}
+
+// The message of a form that cannot be compiled, led by where it is.
+pub fn generate_error(spans: Option<&Rc<KlSpanTree>>, message: String) -> String {
+    match spans {
+        Some(spans) => format!("{}: {}", spans.span, message),
+        None => message
+    }
+}
+
+pub fn generate_application(argument: bool, bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result = Vec::new();
+    let site = spans.map(| s | &s.span);
+    match &*token {
+        &KlToken::Cons(ref application) => {
+            match application.as_slice() {
+                &[ref app @ KlToken::Cons(_), ref rest @ ..] => {
+                    let args = generate_arguments(&bound, rest, spans, 1)?;
+                    let function = intersperse(generate(false, bound.clone(),app,shen_spans_at(spans, &[0]))?,String::from("\n"));
+                    if argument {
+                        result = shen_apply_arguments_to_curried(argument, function, args, site);
+                    }
+                    else {
+                        result = generate_tail_call("shen_apply_closure", function, false, args, site);
+                    }
+                },
+                &[KlToken::Symbol(ref s), ref rest @ ..] => {
+                    let args = generate_arguments(&bound, rest, spans, 1)?;
+                    if bound.iter().any(| b | b.as_str() == &*s.as_str()) {
+                        let function = format!("Rc::new({}_Copy.clone())", s.as_str());
+                        if argument {
+                            result = shen_apply_argument(argument, function, args, site);
+                        }
+                        else {
+                            result = generate_tail_call("shen_apply_element", function, false, args, site);
+                        }
+                    }
+                    else if argument {
+                        result = shen_apply_function(argument, *s, args, site);
+                    }
+                    else {
+                        result = generate_tail_call("shen_apply_arguments_to_function", generate_symbol(*s), true, args, site);
+                    }
+                },
+                &[] => result = generate_element(argument, vec![String::from("shen_nil()")]),
+                &[ref head, ..] => return Err(generate_error(spans, format!("cannot apply {} in {}", head, token)))
+            }
+        },
+        &KlToken::Recur(ref args) => {
+            // println!("{:?}", args);
+            let arg_tuple = generate_arguments(&bound, args, spans, 0)?;
+            let mut args = Vec::new();
+            args.push(String::from("Rc::new(KlElement::Recur(vec!["));
+            args.push(intersperse(arg_tuple, String::from(",")));
+            args.push(String::from("]))"));
+            result = generate_element(argument, args);
+        },
+        _ => return Err(generate_error(spans, format!("{} is not an application", token)))
+    }
+    Ok(result)
+}
+// Application:1 ends here
+
+// [[file:../shen-rust.org::*Generate][Generate:1]]
+pub fn generate(argument: bool, bound: Vec<String>, token: &KlToken, spans: Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String> {
+    let mut result : Vec<String> = Vec::new();
+    let generators : Vec<Box<Fn(bool, Vec<String>, &KlToken, Option<&Rc<KlSpanTree>>) -> Result<Vec<String>, String>>>
+        = vec![
+            Box::new(generate_atoms),
+            Box::new(| _ : bool, bound : Vec<String>, token : &KlToken, spans : Option<&Rc<KlSpanTree>> | generate_defun(bound, token, spans)),
+            Box::new(generate_cond),
+            Box::new(generate_if),
+            Box::new(generate_and_or),
+            Box::new(generate_do),
+            Box::new(generate_trap_error),
+            Box::new(generate_lambda),
+            Box::new(generate_let),
+            Box::new(generate_freeze),
+            Box::new(generate_application)
+        ];
+    for g in generators.as_slice() {
+        if result.len() == 0 {
+            result = g(argument, bound.clone(),token,spans)?;
+        }
+        else {
+            break;
+        }
+    }
+    Ok(result)
+}
+// Generate:1 ends here
+
+// [[file:../shen-rust.org::*Environment][Environment:1]]
+#[derive(Debug)]
+pub struct KlEnvFrame {
+    name: KlSymbol,
+    value: Rc<KlElement>,
+    parent: Option<Rc<KlEnvFrame>>
+}
+
+#[derive(Clone,Debug)]
+pub struct Env {
+    frame: Option<Rc<KlEnvFrame>>
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env { frame: None }
+    }
+
+    pub fn bind(&self, name: KlSymbol, value: Rc<KlElement>) -> Env {
+        Env { frame: Some(Rc::new(KlEnvFrame { name: name, value: value, parent: self.frame.clone() })) }
+    }
+
+    pub fn lookup(&self, name: KlSymbol) -> Option<Rc<KlElement>> {
+        let mut current = self.frame.clone();
+        while let Some(frame) = current {
+            if frame.name == name {
+                return Some(frame.value.clone());
+            }
+            current = frame.parent.clone();
+        }
+        None
+    }
+}
+// Environment:1 ends here
+
+// [[file:../shen-rust.org::*Special%20Forms][Special\ Forms:1]]
+#[derive(Clone,Copy,Debug,PartialEq)]
+pub enum KlSpecialForm {
+    Defun,
+    Lambda,
+    Let,
+    If,
+    Cond,
+    And,
+    Or,
+    Freeze,
+    TrapError,
+    Do,
+    Type
+}
+
+thread_local!(static SPECIAL_FORM_TABLE: HashMap<KlSymbol, KlSpecialForm> = {
+    let mut table = HashMap::new();
+    table.insert(KlSymbol::DEFUN      ,KlSpecialForm::Defun);
+    table.insert(KlSymbol::LAMBDA     ,KlSpecialForm::Lambda);
+    table.insert(KlSymbol::LET        ,KlSpecialForm::Let);
+    table.insert(KlSymbol::IF         ,KlSpecialForm::If);
+    table.insert(KlSymbol::COND       ,KlSpecialForm::Cond);
+    table.insert(KlSymbol::AND        ,KlSpecialForm::And);
+    table.insert(KlSymbol::OR         ,KlSpecialForm::Or);
+    table.insert(KlSymbol::FREEZE     ,KlSpecialForm::Freeze);
+    table.insert(KlSymbol::TRAP_ERROR ,KlSpecialForm::TrapError);
+    table.insert(KlSymbol::DO         ,KlSpecialForm::Do);
+    table.insert(shen_intern_symbol(String::from("type"))       ,KlSpecialForm::Type);
+    table
+});
+
+pub fn shen_lookup_special_form(s: KlSymbol) -> Option<KlSpecialForm> {
+    SPECIAL_FORM_TABLE.with(| table | table.get(&s).cloned())
+}
+// Special\ Forms:1 ends here
+
+// [[file:../shen-rust.org::*Eval][Eval:1]]
+pub fn shen_closure_to_result(c: KlClosure) -> Result<Rc<KlElement>, Rc<KlError>> {
+    match shen_run_trampoline(c) {
+        KlClosure::Done(Err(e)) => Err(e.clone()),
+        c => Ok(shen_closure_to_element(c))
+    }
+}
+
+pub fn shen_string_to_error(s: String) -> Rc<KlError> {
+    Rc::new(KlError::new(KlErrorKind::Runtime, None, s))
+}
+
+pub fn eval_boolean(token: &KlToken, spans: Option<&Rc<KlSpanTree>>, env: &Env, form_name: &str) -> Result<bool, Rc<KlError>> {
+    let value = eval_spanned(token, spans, env)?;
+    match &*value {
+        &KlElement::Symbol(s) if s == KlSymbol::TRUE => Ok(true),
+        &KlElement::Symbol(s) if s == KlSymbol::FALSE => Ok(false),
+        _ => Err(shen_error(KlErrorKind::Type, form_name, "expecting 'true' or 'false'."))
+    }
+}
+
+pub fn eval_curried(env: &Env, args: &[KlSymbol], body: Rc<KlToken>, spans: Option<Rc<KlSpanTree>>) -> KlClosure {
+    match args.split_first() {
+        None => {
+            match eval_in_tail_position(&*body, spans.as_ref(), env) {
+                Ok(v) => shen_element_to_closure(v),
+                Err(e) => KlClosure::Done(Err(e))
+            }
+        },
+        Some((arg, rest)) => {
+            let env = env.clone();
+            let arg = arg.clone();
+            let rest = rest.to_vec();
+            KlClosure::FeedMe(Rc::new(move | a | eval_curried(&env.bind(arg.clone(), a), &rest, body.clone(), spans.clone())))
+        }
+    }
+}
+
+pub fn eval_freeze(env: &Env, body: Rc<KlToken>, spans: Option<Rc<KlSpanTree>>) -> KlClosure {
+    let env = env.clone();
+    KlClosure::Thunk(Rc::new(move || {
+        match eval_in_tail_position(&*body, spans.as_ref(), &env) {
+            Ok(v) => v,
+            Err(e) => Rc::new(KlElement::Closure(KlClosure::Done(Err(e))))
+        }
+    }))
+}
+
+pub fn extract_arg_symbols(args: &[KlToken]) -> Vec<KlSymbol> {
+    args.iter().filter_map(| arg | match arg { &KlToken::Symbol(s) => Some(s), _ => None }).collect()
+}
+
+pub fn eval_defun(args: Vec<KlSymbol>, body: Rc<KlToken>, spans: Option<Rc<KlSpanTree>>) -> KlClosure {
+    if args.len() == 0 {
+        eval_freeze(&Env::new(), body, spans)
+    }
+    else {
+        eval_curried(&Env::new(), args.as_slice(), body, spans)
+    }
+}
+
+// Evaluates the function and its arguments, but leaves the call itself
+// pending for shen_run_trampoline. An error the call ends in is noted as
+// coming out of form.
+pub fn eval_application(form: &Vec<KlToken>, spans: Option<&Rc<KlSpanTree>>, env: &Env) -> Result<Rc<KlElement>, Rc<KlError>> {
+    match form.split_first() {
+        None => Ok(shen_nil()),
+        Some((function, args)) => {
+            let mut arguments = Vec::new();
+            for (i, a) in args.iter().enumerate() {
+                arguments.push(eval_spanned(a, shen_spans_at(spans, &[i + 1]), env)?);
+            }
+            let site = spans.map(| s | s.span.clone());
+            let at_site = move | c: Result<KlClosure, String> | match (c, &site) {
+                (Ok(KlClosure::Done(Err(e))), &Some(ref span)) => Ok(KlClosure::Done(Err(shen_error_at(e, span)))),
+                (c, _) => c
+            };
+            let call = match function {
+                &KlToken::Symbol(s) if env.lookup(s).is_none() =>
+                    shen_tail_call(move || at_site(shen_apply_arguments_to_function(s, arguments.clone()))),
+                _ => {
+                    let f = eval_spanned(function, shen_spans_at(spans, &[0]), env)?;
+                    shen_tail_call(move || at_site(shen_apply_element(f.clone(), arguments.clone())))
+                }
+            };
+            Ok(Rc::new(KlElement::Closure(call)))
+        }
+    }
+}
+
+pub fn eval(token: &KlToken, env: &Env) -> Result<Rc<KlElement>, Rc<KlError>> {
+    eval_spanned(token, None, env)
+}
+
+// eval, for a token whose source spans are known, so that the errors it
+// raises can say where they came from.
+pub fn eval_spanned(token: &KlToken, spans: Option<&Rc<KlSpanTree>>, env: &Env) -> Result<Rc<KlElement>, Rc<KlError>> {
+    let value = eval_in_tail_position(token, spans, env)?;
+    shen_closure_to_result(shen_element_to_closure(value))
+}
+
+// The value of token, except that an application in tail position is
+// returned still pending.
+pub fn eval_in_tail_position(token: &KlToken, spans: Option<&Rc<KlSpanTree>>, env: &Env) -> Result<Rc<KlElement>, Rc<KlError>> {
+    // Arguments and special form operands are evaluated by recursing, so a
+    // deeply nested form runs out of stack here rather than in a call.
+    if shen_stack_exhausted() {
+        return Err(shen_stack_overflow_error());
+    }
+    let mut token = token;
+    let mut spans = spans;
+    let value = eval_forms_in_tail_position(&mut token, &mut spans, env);
+    // spans are now those of the form that raised the error, if one did.
+    value.map_err(| e | match spans {
+        Some(s) => shen_error_at(e, &s.span),
+        None => e
+    })
+}
+
+// Evaluates token, moving token and spans on to each tail context in turn
+// until it reaches a form that has a value or fails.
+fn eval_forms_in_tail_position<'a>(token: &mut &'a KlToken, spans: &mut Option<&'a Rc<KlSpanTree>>, env: &Env) -> Result<Rc<KlElement>, Rc<KlError>> {
+    let mut env = env.clone();
+    loop {
+        let form = match *token {
+            &KlToken::Number(ref n) => return Ok(Rc::new(KlElement::Number(n.clone()))),
+            &KlToken::String(ref s) => return Ok(Rc::new(KlElement::String(s.clone()))),
+            &KlToken::Symbol(s) => {
+                return match env.lookup(s) {
+                    Some(v) => Ok(v),
+                    None => Ok(Rc::new(KlElement::Symbol(s)))
+                }
+            },
+            &KlToken::Recur(_) => return Err(shen_string_to_error(String::from("eval: cannot evaluate a recursion marker."))),
+            &KlToken::Cons(ref form) => form
+        };
+        let at = *spans;
+        let special_form = match form.first() {
+            Some(&KlToken::Symbol(s)) if env.lookup(s).is_none() => shen_lookup_special_form(s),
+            _ => None
+        };
+        match (special_form, form.as_slice()) {
+            (Some(KlSpecialForm::If), &[_, ref predicate, ref if_true, ref if_false]) => {
+                let branch = if eval_boolean(predicate, shen_spans_at(at, &[1]), &env, "if")? { 2 } else { 3 };
+                *token = if branch == 2 { if_true } else { if_false };
+                *spans = shen_spans_at(at, &[branch]);
+            },
+            (Some(KlSpecialForm::And), &[_, ref a, ref b]) => {
+                let result = eval_boolean(a, shen_spans_at(at, &[1]), &env, "and")? &&
+                    eval_boolean(b, shen_spans_at(at, &[2]), &env, "and")?;
+                return Ok(shen_bool_to_symbol(result));
+            },
+            (Some(KlSpecialForm::Or), &[_, ref a, ref b]) => {
+                let result = eval_boolean(a, shen_spans_at(at, &[1]), &env, "or")? ||
+                    eval_boolean(b, shen_spans_at(at, &[2]), &env, "or")?;
+                return Ok(shen_bool_to_symbol(result));
+            },
+            (Some(KlSpecialForm::Cond), &[_, ref cases @ ..]) => {
+                let mut action = None;
+                for (i, case) in cases.iter().enumerate() {
+                    match case {
+                        &KlToken::Cons(ref pair) if pair.len() == 2 => {
+                            if eval_boolean(&pair[0], shen_spans_at(at, &[i + 1, 0]), &env, "cond")? {
+                                action = Some((&pair[1], i + 1));
+                                break;
+                            }
+                        },
+                        _ => return Err(shen_error(KlErrorKind::Type, "cond", "All cases must be pairs."))
+                    }
+                }
+                match action {
+                    Some((action, case)) => {
+                        *token = action;
+                        *spans = shen_spans_at(at, &[case, 1]);
+                    },
+                    None => return Err(shen_error(KlErrorKind::Runtime, "cond", "None of the predicates evaluated to 'true'."))
+                }
+            },
+            (Some(KlSpecialForm::Let), &[_, KlToken::Symbol(ref name), ref value, ref body]) => {
+                let value = eval_spanned(value, shen_spans_at(at, &[2]), &env)?;
+                env = env.bind(*name, value);
+                *token = body;
+                *spans = shen_spans_at(at, &[3]);
+            },
+            (Some(KlSpecialForm::Do), &[_, ref forms @ .., ref last]) => {
+                for (i, f) in forms.iter().enumerate() {
+                    eval_spanned(f, shen_spans_at(at, &[i + 1]), &env)?;
+                }
+                *token = last;
+                *spans = shen_spans_at(at, &[forms.len() + 1]);
+            },
+            (Some(KlSpecialForm::Type), &[_, ref value, _]) => {
+                *token = value;
+                *spans = shen_spans_at(at, &[1]);
+            },
+            (Some(KlSpecialForm::Lambda), &[_, KlToken::Symbol(ref arg), ref body]) => {
+                let lambda = eval_curried(&env, &[*arg], Rc::new(body.clone()), shen_spans_at(at, &[2]).cloned());
+                return Ok(Rc::new(KlElement::Closure(lambda)));
+            },
+            (Some(KlSpecialForm::Freeze), &[_, ref body]) => {
+                return Ok(Rc::new(KlElement::Closure(eval_freeze(&env, Rc::new(body.clone()), shen_spans_at(at, &[1]).cloned()))));
+            },
+            (Some(KlSpecialForm::Defun), &[_, KlToken::Symbol(ref name), KlToken::Cons(ref args), ref body]) => {
+                add_to_function_table(*name, eval_defun(extract_arg_symbols(args), Rc::new(body.clone()), shen_spans_at(at, &[3]).cloned()));
+                return Ok(Rc::new(KlElement::Symbol(*name)));
+            },
+            (Some(KlSpecialForm::TrapError), &[_, ref to_try, ref handler]) => {
+                return match eval_spanned(to_try, shen_spans_at(at, &[1]), &env) {
+                    Ok(v) => Ok(v),
+                    Err(e) => {
+                        let handler = eval_spanned(handler, shen_spans_at(at, &[2]), &env)?;
+                        let exception = Rc::new(KlElement::Exception(e.clone()));
+                        // The handler's body is a tail context, so a call it
+                        // makes is left pending like any other.
+                        match shen_apply_element(handler, vec![exception]) {
+                            Ok(c) => Ok(shen_tail_closure_to_element(c)),
+                            Err(s) => Err(shen_application_error(s.as_str()))
+                        }
+                    }
+                }
+            },
+            _ => return eval_application(form, at, &env)
+        }
+    }
+}
+// Eval:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+pub fn shen_element_to_symbol(s : &KlElement) -> Result<KlSymbol, Rc<String>> {
+    match s {
+        &KlElement::Symbol(s) => Ok(s),
+        _ => Err(Rc::new(String::from("shen_element_to_symbol: Expecting a symbol.")))
+    }
+}
+
+pub fn shen_string_to_symbol(s : &str) -> Rc<KlElement> {
+    Rc::new(KlElement::Symbol(KlSymbol::new(s)))
+}
+
+pub fn shen_bool_to_symbol(b : bool) -> Rc<KlElement> {
+    Rc::new(KlElement::Symbol(KlSymbol::from_bool(b)))
+}
+
+thread_local!(static NIL: Rc<KlElement> = Rc::new(KlElement::Nil));
+
+pub fn shen_nil() -> Rc<KlElement> {
+    NIL.try_with(| nil | nil.clone()).unwrap_or_else(| _ | Rc::new(KlElement::Nil))
+}
+
+// The object returned by (fail), which the kernel uses to mark an empty slot.
+pub fn shen_fail() -> Rc<KlElement> {
+    Rc::new(KlElement::Symbol(KlSymbol::FAIL))
+}
+
+pub fn shen_list(elements: Vec<Rc<KlElement>>) -> Rc<KlElement> {
+    elements.into_iter().rev().fold(shen_nil(), | tail, head | Rc::new(KlElement::Cons(head, tail)))
+}
+
+// The elements of a proper list, or None if the list ends in something other than ().
+pub fn shen_list_elements(list: &Rc<KlElement>) -> Option<Vec<Rc<KlElement>>> {
+    let mut elements = Vec::new();
+    let mut current = list.clone();
+    loop {
+        let next = match &*current {
+            &KlElement::Cons(ref head, ref tail) => {
+                elements.push(head.clone());
+                tail.clone()
+            },
+            &KlElement::Nil => return Some(elements),
+            _ => return None
+        };
+        current = next;
+    }
+}
+
+pub fn shen_is_bool (a: Rc<KlElement>) -> bool {
+    match &*a {
+        &KlElement::Symbol(s) if s == KlSymbol::TRUE || s == KlSymbol::FALSE => true,
+        _ => false
+    }
+}
+
+pub fn shen_is_thunk(a: Rc<KlElement>) -> bool {
+    match &*a {
+        &KlElement::Closure(KlClosure::Thunk(_)) => true,
+        _ => false
+    }
+}
+
+pub fn shen_force_thunk(a : Rc<KlElement>) -> Result<Option<Rc<KlElement>>,Rc<KlError>> {
+    match &*a {
+        &KlElement::Closure(KlClosure::Thunk(ref inner)) => {
+            let value = shen_closure_to_element(shen_element_to_closure(inner()));
+            match &*value {
+                &KlElement::Closure(KlClosure::Done(Err(ref e))) => Err(e.clone()),
+                _ => Ok(Some(value.clone()))
+            }
+        },
+        _ => shen_make_error("shen_force_thunk: Expected a thunk.")
+    }
+}
+
+// Forces a thunk whose value is the caller's own result, so a call the
+// thunk makes in tail position is handed back still pending.
+pub fn shen_force_thunk_in_tail_position(a : Rc<KlElement>) -> KlClosure {
+    match &*a {
+        &KlElement::Closure(KlClosure::Thunk(ref inner)) => shen_element_to_closure(inner()),
+        _ => KlClosure::Done(shen_make_error("shen_force_thunk: Expected a thunk."))
+    }
+}
+
+pub fn shen_make_error(s : &str) -> Result<Option<Rc<KlElement>>, Rc<KlError>> {
+    Err(Rc::new(KlError::new(KlErrorKind::Runtime, None, String::from(s))))
+}
+
+// An error raised by the primitive or special form named origin, given by
+// its KLambda name.
+pub fn shen_error(kind: KlErrorKind, origin: &str, message: &str) -> Rc<KlError> {
+    Rc::new(KlError::new(kind, Some(KlSymbol::intern(origin)), String::from(message)))
+}
+
+// The error for an application that failed before anything was called.
+pub fn shen_application_error(s: &str) -> Rc<KlError> {
+    Rc::new(KlError::new(KlErrorKind::Arity, None, String::from(s)))
+}
+
+pub fn shen_atoms_equal(a: Rc<KlElement>, b: Rc<KlElement>) -> Result<bool, Vec<(Rc<KlElement>, Rc<KlElement>)>> {
+    if Rc::ptr_eq(&a, &b) {
+        return Ok(true)
+    }
+    match (&*a, &*b) {
+        (&KlElement::Symbol(i), &KlElement::Symbol(j)) if i == j => Ok(true),
+        (&KlElement::Number(ref i), &KlElement::Number(ref j)) => Ok(shen_numbers_equal(i, j)),
+        (&KlElement::String(ref i), &KlElement::String(ref j)) if (*i).as_str() == (*j).as_str() => Ok(true),
+        (&KlElement::Nil, &KlElement::Nil) => Ok(true),
+        (&KlElement::Cons(ref head_a, ref tail_a), &KlElement::Cons(ref head_b, ref tail_b)) =>
+            Err(vec![(tail_a.clone(), tail_b.clone()), (head_a.clone(), head_b.clone())]),
+        (&KlElement::Vector(ref i), &KlElement::Vector(ref j)) =>
+            match (&**i,&**j) {
+                (&UniqueVector{vector: ref i}, &UniqueVector{vector: ref j}) => {
+                    let (i, j) = (i.borrow(), j.borrow());
+                    if i.len() == j.len() {
+                        Err(i.iter().cloned().zip(j.iter().cloned()).collect())
+                    }
+                    else {
+                        Ok(false)
+                    }
+                }
+            },
+        _ => Ok(false)
+    }
+}
+
+// Compares the pairs shen_atoms_equal hands back for lists and vectors,
+// keeping a work list instead of recursing. A pair of vectors already being
+// compared is not compared again, so vectors that hold themselves compare
+// in finite time.
+pub fn shen_vector_equal(pairs: Vec<(Rc<KlElement>, Rc<KlElement>)>) -> bool {
+    let mut pending = pairs;
+    let mut compared : HashSet<(*const UniqueVector, *const UniqueVector)> = HashSet::new();
+    while let Some((a, b)) = pending.pop() {
+        if let (&KlElement::Vector(ref i), &KlElement::Vector(ref j)) = (&*a, &*b) {
+            if !compared.insert((&**i as *const UniqueVector, &**j as *const UniqueVector)) {
+                continue;
+            }
+        }
+        match shen_atoms_equal(a, b) {
+            Ok(true) => (),
+            Ok(false) => return false,
+            Err(inner) => pending.extend(inner)
+        }
+    }
+    true
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*If][If:1]]
+pub fn shen_if () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | predicate | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | if_thunk | {
+                            let predicate = predicate.clone();
+                            KlClosure::FeedMe(
+                                Rc::new(
+                                    move | else_thunk | {
+                                        if !shen_is_bool(predicate.clone()) {
+                                            KlClosure::Done(Err(shen_error(KlErrorKind::Type, "if", "the predicate must be 'true' or 'false'.")))
+                                        }
+                                        else {
+                                            if !shen_is_thunk(if_thunk.clone()) || !shen_is_thunk(else_thunk.clone()) {
+                                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "if", "Both the if and else branch must be thunks.")))
+                                            }
+                                            else {
+                                                match *predicate {
+                                                    KlElement::Symbol(s) if s == KlSymbol::TRUE => {
+                                                        shen_force_thunk_in_tail_position(if_thunk.clone())
+                                                    },
+                                                    KlElement::Symbol(s) if s == KlSymbol::FALSE => {
+                                                        shen_force_thunk_in_tail_position(else_thunk.clone())
+                                                    },
+                                                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "if", "Expecting predicate to be 'true' or 'false'.")))
+                                                }
+                                            }
+                                        }
+                                    }
+                                )
+                            )
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// If:1 ends here
+
+// [[file:../shen-rust.org::*And][And:1]]
+pub fn shen_and () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | a_thunk | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | b_thunk | {
+                            if !shen_is_thunk(a_thunk.clone()) || !shen_is_thunk(b_thunk.clone()) {
+                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "and", "Both arguments must be thunks.")))
+                            }
+                            else {
+                                let forced = match shen_force_thunk(a_thunk.clone()) {
+                                    Ok(forced) => forced,
+                                    Err(e) => return KlClosure::Done(Err(e))
+                                };
+                                if forced.is_some() && !shen_is_bool(forced.clone().unwrap()) {
+                                    KlClosure::Done(Err(shen_error(KlErrorKind::Type, "and", "The first argument must evaluate to the symbol 'true' or 'false.")))
+                                }
+                                else {
+                                    let forced : Rc<KlElement> = forced.unwrap();
+                                    match &*forced {
+                                        &KlElement::Symbol(ref a)
+                                            if *a == KlSymbol::FALSE =>
+                                            KlClosure::Done(Ok(Some(shen_bool_to_symbol(false)))),
+                                        _ => {
+                                            let forced = match shen_force_thunk(b_thunk) {
+                                                Ok(forced) => forced,
+                                                Err(e) => return KlClosure::Done(Err(e))
+                                            };
+                                            if forced.is_some() && !shen_is_bool(forced.clone().unwrap()) {
+                                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "and", "The second argument must evaluate to the symbol 'true' or 'false.")))
+                                            }
+                                            else {
+                                                let forced = forced.unwrap();
+                                                match &*forced {
+                                                    &KlElement::Symbol(ref b)
+                                                        if *b == KlSymbol::FALSE =>
+                                                        KlClosure::Done(Ok(Some(shen_bool_to_symbol(false)))),
+                                                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(true))))
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// And:1 ends here
+
+// [[file:../shen-rust.org::*Or][Or:1]]
+pub fn shen_or () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | a_thunk | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | b_thunk | {
+                            if !shen_is_thunk(a_thunk.clone()) || !shen_is_thunk(b_thunk.clone()) {
+                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "or", "Both arguments must be thunks.")))
+                            }
+                            else {
+                                let forced = match shen_force_thunk(a_thunk.clone()) {
+                                    Ok(forced) => forced,
+                                    Err(e) => return KlClosure::Done(Err(e))
+                                };
+                                if forced.is_some() && !shen_is_bool(forced.clone().unwrap()) {
+                                    KlClosure::Done(Err(shen_error(KlErrorKind::Type, "or", "The first argument must evaluate to the symbol 'true' or 'false.")))
+                                }
+                                else {
+                                    let forced : Rc<KlElement> = forced.unwrap();
+                                    match &*forced {
+                                        &KlElement::Symbol(ref a)
+                                            if *a == KlSymbol::TRUE =>
+                                            KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                                        _ => {
+                                            let forced = match shen_force_thunk(b_thunk) {
+                                                Ok(forced) => forced,
+                                                Err(e) => return KlClosure::Done(Err(e))
+                                            };
+                                            if forced.is_some() && !shen_is_bool(forced.clone().unwrap()) {
+                                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "or", "The second argument must evaluate to the symbol 'true' or 'false.")))
+                                            }
+                                            else {
+                                                let forced = forced.unwrap();
+                                                match &*forced {
+                                                    &KlElement::Symbol(ref b)
+                                                        if *b == KlSymbol::TRUE =>
+                                                        KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                                                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false))))
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// Or:1 ends here
+
+// [[file:../shen-rust.org::*Cond][Cond:1]]
+pub fn shen_cond() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | cases | {
+                match shen_list_elements(&cases) {
+                    Some(case_pairs) => {
+                        let mut pairs : Vec<(Rc<KlElement>,Rc<KlElement>)>= Vec::new();
+                        for case in case_pairs {
+                            match shen_list_elements(&case) {
+                                Some(ref pair) if pair.len() == 2 => {
+                                    let ref predicate = pair[0];
+                                    let ref action = pair[1];
+                                    if !shen_is_thunk(predicate.clone()) || !shen_is_thunk(action.clone()) {
+                                        return KlClosure::Done(Err(shen_error(KlErrorKind::Type, "cond", "All cases must be a pairs of thunks.")))
+                                    }
+                                    else {
+                                        pairs.push((predicate.clone(),action.clone()))
+                                    }
+                                },
+                                _ => return KlClosure::Done(Err(shen_error(KlErrorKind::Type, "cond", "All cases must be pairs.")))
+                            }
+                        };
+                        let mut result = None;
+                        for &(ref predicate,ref action) in pairs.as_slice() {
+                            let forced = match shen_force_thunk(predicate.clone()) {
+                                Ok(forced) => forced,
+                                Err(e) => return KlClosure::Done(Err(e))
+                            };
+                            if forced.is_some() && !shen_is_bool(forced.clone().unwrap()) {
+                                result = Some(KlClosure::Done(Err(shen_error(KlErrorKind::Type, "cond", "All predicates must evaluate to 'true' or 'false'."))))
+                            }
+                            else {
+                                let forced = forced.unwrap();
+                                match &*forced {
+                                    &KlElement::Symbol(s) if s == KlSymbol::TRUE => {
+                                        result = Some(shen_force_thunk_in_tail_position(action.clone()));
+                                        break;
+                                    },
+                                    _ => ()
+                                }
+                            }
+                        }
+                        match result {
+                            Some(r) => r,
+                            None => KlClosure::Done(Err(shen_error(KlErrorKind::Runtime, "cond", "None of the predicates evaluated to 'true'.")))
+
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "cond", "All cases must be a pairs of thunks.")))
+                }
+            }
+        )
+    )
+}
+// Cond:1 ends here
+
+// [[file:../shen-rust.org::*Intern][Intern:1]]
+pub fn shen_intern() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | string | {
+                match &*string {
+                    &KlElement::String(ref s) => {
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::Symbol(shen_intern_symbol(s.clone()))))))
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "intern", "expecting a string.")))
+                }
+            }
+        )
+    )
+}
+// Intern:1 ends here
+
+// [[file:../shen-rust.org::*pos][pos:1]]
+pub fn shen_pos() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | string | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | number | {
+                            let string = string.clone();
+                            match &*string {
+                                &KlElement::String(ref s) => {
+                                    let length = (&s).chars().count();
+                                    match &*number {
+                                        &KlElement::Number(KlNumber::Int(i)) if i >= 0 && (i as usize) < length => {
+                                            let char = (*s).chars().nth(i as usize).unwrap();
+                                            let mut result = String::from("");
+                                            result.push(char);
+                                            KlClosure::Done(Ok(Some(Rc::new(KlElement::String(result)))))
+                                        },
+                                        _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "pos", "expecting a number between 0 and the length of the string.")))
+                                    }
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "pos", "expecting a string.")))
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// pos:1 ends here
+
+// [[file:../shen-rust.org::*tlstr][tlstr:1]]
+pub fn shen_tlstr() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | string | {
+                match &*string {
+                    &KlElement::String(ref s) => {
+                        let mut chars = s.chars();
+                        match chars.next() {
+                            None => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "tlstr", "expecting non-empty string."))),
+                            Some(_) => KlClosure::Done(Ok(Some(Rc::new(KlElement::String(String::from(chars.as_str()))))))
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "tlstr", "expecting a string.")))
+                }
+
+            }
+        )
+    )
+}
+// tlstr:1 ends here
+
+// [[file:../shen-rust.org::*cn][cn:1]]
+pub fn shen_cn () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | string_a | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | string_b | {
+                            let string_a = string_a.clone();
+                            match (&*string_a, &*string_b) {
+                                (&KlElement::String(ref a), &KlElement::String(ref b)) => {
+                                    KlClosure::Done(Ok(Some(Rc::new(KlElement::String((*a).clone() + b)))))
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "cn", "expecting two strings.")))
+                            }
+
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// cn:1 ends here
+
+// [[file:../shen-rust.org::*str][str:1]]
+pub fn shen_str() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | atom | {
+                match &*atom {
+                    &KlElement::String(_) => KlClosure::Done(Ok(Some(atom.clone()))),
+                    &KlElement::Number(ref n) =>
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::String(print_kl_number(n)))))),
+                    &KlElement::Symbol(s) =>
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::String(shen_symbol_name(s)))))),
+                    &KlElement::Stream(ref s) => {
+                        match &**s {
+                            &KlStream::FileStream(_) =>
+                                KlClosure::Done(Ok(Some(Rc::new(KlElement::String(String::from("<file stream>")))))),
+                            &KlStream::Std(KlStdStream::Stdout) =>
+                                KlClosure::Done(Ok(Some(Rc::new(KlElement::String(String::from("<stdout>")))))),
+                            &KlStream::Std(KlStdStream::Stdin) =>
+                                KlClosure::Done(Ok(Some(Rc::new(KlElement::String(String::from("<stdin>")))))),
+                        }
+                    }
+                    &KlElement::Exception(_) =>
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::String(format!("{}", atom)))))),
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "str", "Not an atom, stream or closure; cannot convert it to a string.")))
+                }
+            }
+        )
+    )
+}
+// str:1 ends here
+
+// [[file:../shen-rust.org::*string?][string\?:1]]
+pub fn shen_stringp() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | element | {
+                match &*element {
+                    &KlElement::String(_) =>
+                        KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false))))
+                }
+            }
+        )
+    )
+}
+// string\?:1 ends here
+
+// [[file:../shen-rust.org::*n->string][n->string:1]]
+pub fn shen_n_to_string() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | n | {
+                match &*n {
+                    &KlElement::Number(KlNumber::Int(i)) => {
+                        let convert : Result<u32, _>= TryFrom::try_from(i);
+                        match convert.ok().and_then(std::char::from_u32) {
+                            Some(char) => KlClosure::Done(Ok(Some(Rc::new(KlElement::String(char.to_string()))))),
+                            None => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "n->string", "number is not a unicode code point.")))
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "n->string", "expecting an integer.")))
+                }
+                }
+        )
+    )
+}
+// n->string:1 ends here
+
+// [[file:../shen-rust.org::*string->n][string->n:1]]
+pub fn shen_string_to_n() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | string | {
+                match &*string {
+                    &KlElement::String(ref s) if s.chars().count() == 1 => {
+                        let c = s.chars().next().unwrap();
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(KlNumber::Int(c as i64))))))
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "string->n", "expecting a unit string.")))
+
+                }
+            }
+        )
+    )
+}
+// string->n:1 ends here
+
+// [[file:../shen-rust.org::*simple-error][simple-error:1]]
+pub fn shen_simple_error () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | error | {
+                match *error {
+                    KlElement::String(ref s) => {
+                        KlClosure::Done(Err(Rc::new(KlError::new(KlErrorKind::User, None, s.clone()))))
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "simple-error", "Expecting a string.")))
+                }
+            }
+        )
+    )
+}
+// simple-error:1 ends here
+
+// [[file:../shen-rust.org::*trap-error][trap-error:1]]
+pub fn shen_trap_error() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | to_try_thunk | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | handler | {
+                            let to_try_thunk = to_try_thunk.clone();
+                            if !shen_is_thunk(to_try_thunk.clone()) {
+                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "trap-error", "Expecting a thunk.")))
+                            }
+                            else {
+                                match &*handler {
+                                    &KlElement::Closure(KlClosure::FeedMe(ref f)) => {
+                                        let forced = shen_force_thunk(to_try_thunk.clone())
+                                            .and_then(| r | shen_closure_to_result(r.map_or(KlClosure::Done(Ok(None)), shen_element_to_closure)));
+                                        match forced {
+                                            Ok(r) => { KlClosure::Done(Ok(Some(r))) },
+                                            Err(s) => {
+                                                let exception = Rc::new(KlElement::Exception(s.clone()));
+                                                (&f)(exception.clone())
+                                            }
+                                        }
+                                    },
+                                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "trap-error", "Expecting a closure.")))
+                                }
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// trap-error:1 ends here
+
+// [[file:../shen-rust.org::*error-to-string][error-to-string:1]]
+pub fn shen_error_to_string() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | exception | {
+                match &*exception {
+                    &KlElement::Exception(ref e) =>
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::String(e.message()))))),
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "error-to-string", "expecting an exception.")))
+                }
+            }
+        )
+    )
+}
+// error-to-string:1 ends here
+
+// [[file:../shen-rust.org::*error-trace][error-trace:1]]
+// The functions an exception unwound through before it was caught, one
+// line each, as printed after its message when nothing catches it.
+pub fn shen_error_trace() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | exception | {
+                match &*exception {
+                    &KlElement::Exception(ref e) => {
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::String(shen_format_error_trace(e))))))
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "shen-rust.error-trace", "expecting an exception.")))
+                }
+            }
+        )
+    )
+}
+
+// The kind of an exception as a symbol, one of user, type, arity, io,
+// undefined, stack-overflow, no-value and runtime.
+pub fn shen_error_kind() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | exception | {
+                match &*exception {
+                    &KlElement::Exception(ref e) => {
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::Symbol(KlSymbol::intern(e.kind.name()))))))
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "shen-rust.error-kind", "expecting an exception.")))
+                }
+            }
+        )
+    )
+}
+
+// The primitive or special form that raised an exception, or the empty
+// list if the runtime raised it itself.
+pub fn shen_error_origin() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | exception | {
+                match &*exception {
+                    &KlElement::Exception(ref e) => {
+                        match e.origin {
+                            Some(origin) => KlClosure::Done(Ok(Some(Rc::new(KlElement::Symbol(origin))))),
+                            None => KlClosure::Done(Ok(Some(shen_nil())))
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "shen-rust.error-origin", "expecting an exception.")))
+                }
+            }
+        )
+    )
+}
+// error-trace:1 ends here
+
+// [[file:../shen-rust.org::*Set][Set:1]]
+pub fn shen_set () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | symbol | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | value | {
+                            let symbol = symbol.clone();
+                            SYMBOL_TABLE.with(| symbol_table | {
+                                let mut map = symbol_table.borrow_mut();
+                                match shen_element_to_symbol(&*symbol) {
+                                    Ok(s) => {
+                                        map.insert(s, value.clone());
+                                        return KlClosure::Done(Ok(Some(value)))
+                                    }
+                                    _ => return KlClosure::Done(Err(shen_error(KlErrorKind::Type, "set", "expecting a symbol for a key.")))
+                                }
+                            })
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// Set:1 ends here
+
+// [[file:../shen-rust.org::*Value][Value:1]]
+pub fn shen_value() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | symbol | {
+                SYMBOL_TABLE.with(| symbol_table| {
+                    let map = symbol_table.borrow();
+                    match shen_element_to_symbol(&*symbol) {
+                        Ok(s) => {
+                            match map.get(&s) {
+                                Some(v) => KlClosure::Done(Ok(Some(v.clone()))),
+                                None => KlClosure::Done(Err(shen_error(KlErrorKind::Runtime, "value", &*(format!("variable {} is unbound", shen_symbol_name(s))))))
+                            }
+                        },
+                        _ => return KlClosure::Done(Err(shen_error(KlErrorKind::Type, "value", "expecting a symbol for a key.")))
+                    }
+                })
+            }
+        )
+    )
+}
+// Value:1 ends here
+
+// [[file:../shen-rust.org::*Cons][Cons:1]]
+pub fn shen_cons() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | new_head | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | list | {
+                            KlClosure::Done(Ok(Some(Rc::new(KlElement::Cons(new_head.clone(), list)))))
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// Cons:1 ends here
+
+// [[file:../shen-rust.org::*Head][Head:1]]
+pub fn shen_hd() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | list | {
+                match *list {
+                    KlElement::Cons(ref head, _) => KlClosure::Done(Ok(Some(head.clone()))),
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "hd", "Expecting a non-empty list.")))
+                }
+            }
+        )
+    )
+}
+// Head:1 ends here
+
+// [[file:../shen-rust.org::*Tail][Tail:1]]
+pub fn shen_tl() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | list | {
+                match *list {
+                    KlElement::Cons(_, ref tail) => KlClosure::Done(Ok(Some(tail.clone()))),
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "tl", "Expecting a non-empty list.")))
+                }
+            }
+        )
+    )
+}
+// Tail:1 ends here
+
+// [[file:../shen-rust.org::*Cons?][Cons\?:1]]
+pub fn shen_consp() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | list | {
+                match *list {
+                    KlElement::Cons(_, _) => KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false))))
+                }
+            }
+        )
+    )
+}
+// Cons\?:1 ends here
+
+// [[file:../shen-rust.org::*=][=:1]]
+pub fn shen_equal() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | a | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | b | {
+                            let a = a.clone();
+                            let is_equal =
+                                match shen_atoms_equal(a,b) {
+                                    Ok(equal) => equal,
+                                    Err(pairs) => shen_vector_equal(pairs)
+                                };
+                            KlClosure::Done(
+                                Ok(Some(shen_bool_to_symbol(is_equal)))
+                            )
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// =:1 ends here
+
+// [[file:../shen-rust.org::*absvector][absvector:1]]
+pub fn shen_absvector() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | size | {
+                match *size {
+                    KlElement::Number(KlNumber::Int(n)) if n >= 0 => {
+                        let mut v = Vec::new();
+                        match v.try_reserve_exact(n as usize) {
+                            Ok(()) => {
+                                v.resize(n as usize, shen_fail());
+                                let unique_vector = Rc::new(UniqueVector{ vector: RefCell::new(v) });
+                                KlClosure::Done(Ok(Some(Rc::new(KlElement::Vector(unique_vector)))))
+                            },
+                            Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Runtime, "absvector", &*format!("Cannot allocate a vector of {} elements.", n))))
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "absvector", "Expecting a non-negative integer.")))
+                }
+            }
+        )
+    )
+}
+// absvector:1 ends here
+
+// [[file:../shen-rust.org::*address->][address->:1]]
+pub fn shen_insert_at_address() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | vector | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | index | {
+                            let vector = vector.clone();
+                            KlClosure::FeedMe(
+                                Rc::new(
+                                    move | value | {
+                                        match &*vector {
+                                            &KlElement::Vector(ref unique_vector) => {
+                                                match *index {
+                                                    KlElement::Number(KlNumber::Int(i)) if i >= 0 => {
+                                                        let mut payload = (**unique_vector).vector.borrow_mut();
+                                                        let length = payload.len();
+                                                        if (i as usize) < length {
+                                                            payload[i as usize] = value.clone();
+                                                            KlClosure::Done(Ok(Some(vector.clone())))
+                                                        }
+                                                        else {
+                                                            KlClosure::Done(Err(shen_error(KlErrorKind::Type, "address->", "Expecting an index less than the vector length.")))
+                                                        }
+                                                    },
+                                                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "address->", "Expecting a non-negative integer index.")))
+                                                }
+                                            },
+                                            _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "address->", "Expecting a vector.")))
+                                        }
+                                    }
+                                )
+                            )
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// address->:1 ends here
+
+// [[file:../shen-rust.org::*<-address][<-address:1]]
+pub fn shen_get_at_address() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | vector | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | index | {
+                            let vector = vector.clone();
+                            match &*vector {
+                                &KlElement::Vector(ref unique_vector) => {
+                                    match *index {
+                                        KlElement::Number(KlNumber::Int(i)) if i >= 0 => {
+                                            let payload = (**unique_vector).vector.borrow();
+                                            let length = payload.len();
+                                            if (i as usize) < length {
+                                                let ref found = payload[i as usize];
+                                                KlClosure::Done(Ok(Some((*found).clone())))
+                                            }
+                                            else {
+                                                KlClosure::Done(Err(shen_error(KlErrorKind::Type, "<-address", "Expecting an index less than the vector length.")))
+                                            }
+                                        },
+                                        _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "<-address", "Expecting a non-negative integer index.")))
+                                    }
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "<-address", "Expecting a vector.")))
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// <-address:1 ends here
+
+// [[file:../shen-rust.org::*absvector?][absvector\?:1]]
+pub fn shen_absvectorp() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | vector | {
+                match &*vector {
+                    &KlElement::Vector(_) => KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false)))),
+                }
+            }
+        )
+    )
+}
+// absvector\?:1 ends here
+
+// [[file:../shen-rust.org::*write-byte][write-byte:1]]
+pub fn shen_write_byte () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | to_write | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | stream | {
+                            let byte = to_write.clone();
+                            match &*byte {
+                                &KlElement::Number(KlNumber::Int(i)) => {
+                                    let converted = TryFrom::try_from(i);
+                                    match converted {
+                                        Ok(byte) => {
+                                            match *stream {
+                                                KlElement::Stream(ref stream) => {
+                                                    let stream : &KlStream = &*stream;
+                                                    match stream {
+                                                        &KlStream::FileStream(KlFileStream { direction: KlStreamDirection::Out, file: ref handle }) => {
+                                                            let mut file = (*handle).borrow_mut();
+                                                            let written = file.write(&[byte]);
+                                                            match written {
+                                                                Ok(_) => KlClosure::Done(Ok(Some(to_write.clone()))),
+                                                                Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Io, "write-byte", "Could not write byte to file.")))
+                                                            }
+                                                        },
+                                                        &KlStream::Std(KlStdStream::Stdout) => {
+                                                            let written = io::stdout().write(&[byte]);
+                                                            match written {
+                                                                Ok(_) => KlClosure::Done(Ok(Some(to_write.clone()))),
+                                                                Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Io, "write-byte", "Could not write byte to stdout.")))
+                                                            }
+                                                        }
+                                                        _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "write-byte", "Expecting a write-only stream or stdout.")))
+                                                    }
+                                                },
+                                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "write-byte", "Expecting a stream.")))
+                                            }
+                                        },
+                                        Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "write-byte", "Expecting a byte.")))
+                                    }
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "write-byte", "Expecting a number.")))
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// write-byte:1 ends here
+
+// [[file:../shen-rust.org::*read-byte][read-byte:1]]
+thread_local!(static END_OF_STDIN_HOOK: RefCell<Option<Box<Fn()>>> = RefCell::new(None));
+
+// Calls hook whenever read-byte finds stdin at its end on this thread. The
+// read still gives -1, as it does for any stream, so hook is how a driver
+// whose toplevel would read on forever learns that its input is over.
+pub fn shen_on_end_of_stdin(hook: Box<Fn()>) {
+    END_OF_STDIN_HOOK.with(| end_of_stdin | *end_of_stdin.borrow_mut() = Some(hook));
+}
+
+pub fn shen_read_byte () -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            move | stream | {
+                match *stream {
+                    KlElement::Stream(ref stream) => {
+                        let stream : &KlStream = &*stream;
+                        let mut buffer = [0; 1];
+                        let read = match stream {
+                            &KlStream::FileStream(KlFileStream { direction: KlStreamDirection::In, file: ref handle }) => {
+                                let mut file = (*handle).borrow_mut();
+                                file.read(&mut buffer[..])
+                            },
+                            &KlStream::Std(KlStdStream::Stdin) => {
+                                let _ = io::stdout().flush();
+                                let read = io::stdin().read(&mut buffer[..]);
+                                if let Ok(0) = read {
+                                    END_OF_STDIN_HOOK.with(| end_of_stdin | end_of_stdin.borrow().as_ref().map(| hook | hook()));
+                                }
+                                read
+                            }
+                            _ => Err(Error::new(std::io::ErrorKind::Other, "shen_read_byte: Expecting a read-only stream or stdin."))
+                        };
+                        match read {
+                            Ok(0) => KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(KlNumber::Int(-1)))))),
+                            Ok(_) => KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(KlNumber::Int(buffer[0] as i64)))))),
+                            Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Io, "read-byte", "Could not read byte.")))
+                        }
+
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "read-byte", "Expecting a stream.")))
+                }
+            }
+        )
+    )
+}
+// read-byte:1 ends here
+
+// [[file:../shen-rust.org::*Open][Open:1]]
+pub fn shen_open() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | file_name | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | direction | {
+                            let file_name = file_name.clone();
+                            match &*file_name {
+                                &KlElement::String(ref path) => {
+                                    let path = path.as_str();
+                                    match &*direction {
+                                        &KlElement::Symbol(direction) if direction == KlSymbol::IN => {
+                                            match File::open(path) {
+                                                Ok(f) =>
+                                                    KlClosure::Done(
+                                                        Ok(Some(Rc::new(KlElement::Stream(Rc::new(
+                                                            KlStream::FileStream(
+                                                                KlFileStream {
+                                                                    direction: KlStreamDirection::In,
+                                                                    file: RefCell::new(f)}))))))),
+                                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Io, "open", "Could not open file.")))
+                                            }
+                                        },
+                                        _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "open", "Expecting direction 'in'.")))
+                                    }
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "open", "Expecting a file path.")))
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// Open:1 ends here
+
+// [[file:../shen-rust.org::*Close][Close:1]]
+pub fn shen_close() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | stream | {
+                match *stream {
+                    KlElement::Stream(ref stream) => {
+                        match &**stream {
+                            &KlStream::FileStream(KlFileStream { file: ref handle, .. }) => {
+                                match (*handle).borrow_mut().flush() {
+                                    Ok(_) => KlClosure::Done(Ok(Some(shen_nil()))),
+                                    Err(_) => KlClosure::Done(Err(shen_error(KlErrorKind::Io, "close", "Could not flush stream.")))
+                                }
+                            },
+                            &KlStream::Std(_) => KlClosure::Done(Ok(Some(shen_nil())))
+                        }
+                    },
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "close", "Expecting a stream.")))
+                }
+            }
+        )
+    )
+}
+// Close:1 ends here
+
+// [[file:../shen-rust.org::*get-time][get-time:1]]
+pub fn shen_get_time() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | time_type | {
+                match &*time_type {
+                    &KlElement::Symbol(s) if s == KlSymbol::RUN || s == KlSymbol::REAL => {
+                        KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(KlNumber::Float(time::precise_time_s()))))))
+                    }
+                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "get-time", "Expecting 'run' or 'real'.")))
+                }
+            }
+        )
+    )
+}
+// get-time:1 ends here
+
+// [[file:../shen-rust.org::*Macros][Macros:1]]
+macro_rules! number_op {
+    ($a:ident, $b:ident, $checked_op:ident, $float_op:ident, $fn_name:expr, $op_name:expr) => {
+        KlClosure::FeedMe(
+            Rc::new(
+                | $a | {
+                    KlClosure::FeedMe(
+                        Rc::new(
+                            move | $b | {
+                                let $a = $a.clone();
+                                match (&*$a, &*$b) {
+                                    (&KlElement::Number(ref a), &KlElement::Number(ref b)) => {
+                                        let result = match (a, b) {
+                                            (&KlNumber::Float(_), _) | (_, &KlNumber::Float(_)) =>
+                                                Some(KlNumber::Float(shen_number_to_float(a).$float_op(shen_number_to_float(b)))),
+                                            (&KlNumber::Int(i), &KlNumber::Int(j)) =>
+                                                i.$checked_op(j).map(KlNumber::Int)
+                                                    .or_else(|| BigInt::from(i).$checked_op(&BigInt::from(j)).map(shen_demote_big)),
+                                            _ => shen_number_to_big(a).$checked_op(&shen_number_to_big(b)).map(shen_demote_big)
+                                        };
+                                        match result {
+                                            Some(n) => KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(n))))),
+                                            None => KlClosure::Done(Err(shen_error(KlErrorKind::Runtime, $fn_name, format!("{} would cause overflow.", $op_name).as_str())))
+                                        }
+                                    },
+                                    _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, $fn_name, "expecting two numbers.")))
+                                }
+                            }
+                        )
+                    )
+                }
+            )
+        )
+    }
+}
+
+macro_rules! number_test {
+    ($a:ident, $b:ident, $test:ident, $fn_name:expr) => {
+        KlClosure::FeedMe(
+            Rc::new(
+                | $a | {
+                    KlClosure::FeedMe(
+                        Rc::new(
+                            move | $b | {
+                                let $a = $a.clone();
+                                let test_result =
+                                    match (&*$a, &*$b) {
+                                        (&KlElement::Number(ref a), &KlElement::Number(ref b)) =>
+                                            Some(shen_compare_numbers(a, b).map_or(false, $test)),
+                                        _ => None
+                                    };
+                                match test_result {
+                                    Some(true) => KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                                    Some(false) => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false)))),
+                                    None => KlClosure::Done(Err(shen_error(KlErrorKind::Type, $fn_name, "expecting two numbers.")))
+                                }
+                            }
+                        )
+                    )
+                }
+            )
+        )
+    }
+}
+// Macros:1 ends here
+
+// [[file:../shen-rust.org::*Helpers][Helpers:1]]
+pub fn shen_le_shim(o: Ordering) -> bool {
+    o == Ordering::Less
+}
+pub fn shen_ge_shim(o: Ordering) -> bool {
+    o == Ordering::Greater
+}
+pub fn shen_eq_ge_shim(o: Ordering) -> bool {
+    o != Ordering::Less
+}
+pub fn shen_eq_le_shim(o: Ordering) -> bool {
+    o != Ordering::Greater
+}
+
+// Integer results are kept as Int whenever they fit, so Big only ever
+// holds numbers outside the range of an i64.
+pub fn shen_demote_big(b: BigInt) -> KlNumber {
+    match b.to_i64() {
+        Some(i) => KlNumber::Int(i),
+        None => KlNumber::Big(b)
+    }
+}
+
+pub fn shen_big_number(digits: &str) -> KlNumber {
+    shen_demote_big(digits.parse::<BigInt>().unwrap_or(BigInt::zero()))
+}
+
+pub fn shen_number_to_big(n: &KlNumber) -> BigInt {
+    match n {
+        &KlNumber::Int(i) => BigInt::from(i),
+        &KlNumber::Big(ref b) => b.clone(),
+        &KlNumber::Float(f) => BigInt::from(f as i64)
+    }
+}
+
+// Numbers are equal when they denote the same value, so (= 1 1.0) is true.
+pub fn shen_numbers_equal(a: &KlNumber, b: &KlNumber) -> bool {
+    shen_compare_numbers(a, b) == Some(Ordering::Equal)
+}
+
+// Shen division is exact: two integers give an integer only when the
+// divisor goes into the dividend, and a float otherwise.
+pub fn shen_divide_numbers(a: &KlNumber, b: &KlNumber) -> Result<KlNumber, String> {
+    if shen_number_to_float(b) == 0.0 {
+        return Err(String::from("division by zero."))
+    }
+    match (a, b) {
+        (&KlNumber::Float(_), _) | (_, &KlNumber::Float(_)) =>
+            Ok(KlNumber::Float(shen_number_to_float(a) / shen_number_to_float(b))),
+        _ => {
+            let (dividend, divisor) = (shen_number_to_big(a), shen_number_to_big(b));
+            if (&dividend % &divisor).is_zero() {
+                Ok(shen_demote_big(dividend / divisor))
+            }
+            else {
+                Ok(KlNumber::Float(shen_ratio_to_float(&dividend, &divisor)))
+            }
+        }
+    }
+}
+
+// dividend / divisor as a float. The division is done on integers scaled
+// to leave a quotient of about 64 significant bits, which is converted to
+// a float once, so neither operand has to fit in an f64 on its own.
+fn shen_ratio_to_float(dividend: &BigInt, divisor: &BigInt) -> f64 {
+    let shift = divisor.bits() as i64 + 64 - dividend.bits() as i64;
+    let quotient = if shift >= 0 {
+        (dividend << shift as usize) / divisor
+    }
+    else {
+        dividend / (divisor << (-shift) as usize)
+    };
+    let mut scaled = shen_number_to_float(&KlNumber::Big(quotient));
+    let mut exponent = -shift;
+    while exponent > 1000 {
+        scaled *= 2f64.powi(1000);
+        exponent -= 1000;
+    }
+    while exponent < -1000 {
+        scaled *= 2f64.powi(-1000);
+        exponent += 1000;
+    }
+    scaled * 2f64.powi(exponent as i32)
+}
+
+// A Big too large for an f64 saturates to the infinity of its sign.
+pub fn shen_number_to_float(n: &KlNumber) -> f64 {
+    match n {
+        &KlNumber::Int(i) => i as f64,
+        &KlNumber::Big(ref b) => {
+            match (b.to_f64(), b.sign()) {
+                (Some(f), _) => f,
+                (None, Sign::Minus) => std::f64::NEG_INFINITY,
+                (None, _) => std::f64::INFINITY
+            }
+        },
+        &KlNumber::Float(f) => f
+    }
+}
+
+// Orders two numbers by the values they denote. An integer is compared
+// with a float exactly rather than after rounding it to a float, so a Big
+// beyond the range of an f64 is still greater than every finite float.
+// None when either number is NaN.
+pub fn shen_compare_numbers(a: &KlNumber, b: &KlNumber) -> Option<Ordering> {
+    match (a, b) {
+        (&KlNumber::Float(x), &KlNumber::Float(y)) => x.partial_cmp(&y),
+        (&KlNumber::Float(x), _) => shen_compare_integer_to_float(b, x).map(Ordering::reverse),
+        (_, &KlNumber::Float(y)) => shen_compare_integer_to_float(a, y),
+        (&KlNumber::Int(i), &KlNumber::Int(j)) => Some(i.cmp(&j)),
+        _ => Some(shen_number_to_big(a).cmp(&shen_number_to_big(b)))
+    }
+}
+
+fn shen_compare_integer_to_float(n: &KlNumber, f: f64) -> Option<Ordering> {
+    if f.is_infinite() {
+        return Some(if f > 0.0 { Ordering::Less } else { Ordering::Greater })
+    }
+    BigInt::from_f64(f.floor()).map(| floor | {
+        match shen_number_to_big(n).cmp(&floor) {
+            Ordering::Equal if f != f.floor() => Ordering::Less,
+            ordering => ordering
+        }
+    })
+}
+// Helpers:1 ends here
+
+// [[file:../shen-rust.org::*+][+:1]]
+pub fn shen_plus() -> KlClosure {
+    number_op!(number_a, number_b, checked_add, add, "+", "adding")
+}
+// +:1 ends here
+
+// [[file:../shen-rust.org::**][*:1]]
+pub fn shen_mul() -> KlClosure {
+    number_op!(number_a, number_b, checked_mul, mul, "*", "multiplying")
+}
+// *:1 ends here
+
+// [[file:../shen-rust.org::*-][-:1]]
+pub fn shen_sub() -> KlClosure {
+    number_op!(number_a, number_b, checked_sub, sub, "-", "subtracting")
+}
+// -:1 ends here
+
+// [[file:../shen-rust.org::*/][/:1]]
+pub fn shen_div() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | number_a | {
+                KlClosure::FeedMe(
+                    Rc::new(
+                        move | number_b | {
+                            match (&*number_a, &*number_b) {
+                                (&KlElement::Number(ref a), &KlElement::Number(ref b)) => {
+                                    match shen_divide_numbers(a, b) {
+                                        Ok(n) => KlClosure::Done(Ok(Some(Rc::new(KlElement::Number(n))))),
+                                        Err(s) => KlClosure::Done(Err(shen_error(KlErrorKind::Runtime, "/", s.as_str())))
+                                    }
+                                },
+                                _ => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "/", "expecting two numbers.")))
+                            }
+                        }
+                    )
+                )
+            }
+        )
+    )
+}
+// /:1 ends here
+
+// [[file:../shen-rust.org::*>][>:1]]
+pub fn shen_ge() -> KlClosure {
+    number_test!(number_a, number_b, shen_ge_shim, ">")
+}
+// >:1 ends here
+
+// [[file:../shen-rust.org::*<][<:1]]
+pub fn shen_le() -> KlClosure {
+    number_test!(number_a, number_b, shen_le_shim, "<")
+}
+// <:1 ends here
+
+// [[file:../shen-rust.org::*>=][>=:1]]
+pub fn shen_eq_le() -> KlClosure {
+    number_test!(number_a, number_b, shen_eq_le_shim, "<=")
+}
+// >=:1 ends here
+
+// [[file:../shen-rust.org::*<=][<=:1]]
+pub fn shen_eq_ge() -> KlClosure {
+    number_test!(number_a, number_b, shen_eq_ge_shim, ">=")
+}
+// <=:1 ends here
+
+// [[file:../shen-rust.org::*number?][number\?:1]]
+pub fn shen_numberp() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | number | {
+                match &*number {
+                    &KlElement::Number(_) => KlClosure::Done(Ok(Some(shen_bool_to_symbol(true)))),
+                    _ => KlClosure::Done(Ok(Some(shen_bool_to_symbol(false))))
+                }
+            }
+        )
+    )
+}
+// number\?:1 ends here
+
+// [[file:../shen-rust.org::*eval-kl][eval-kl:1]]
+pub fn shen_element_to_token(element: &KlElement) -> Result<KlToken, String> {
+    match element {
+        &KlElement::Symbol(s) => Ok(KlToken::Symbol(s)),
+        &KlElement::Number(ref n) => Ok(KlToken::Number(n.clone())),
+        &KlElement::String(ref s) => Ok(KlToken::String(s.clone())),
+        &KlElement::Nil => Ok(KlToken::Cons(vec![])),
+        &KlElement::Cons(_, _) => {
+            let mut tokens = Vec::new();
+            let mut current = element;
+            while let &KlElement::Cons(ref head, ref tail) = current {
+                tokens.push(shen_element_to_token(&*head)?);
+                current = &**tail;
+            }
+            match current {
+                &KlElement::Nil => Ok(KlToken::Cons(tokens)),
+                _ => Err(String::from("Cannot evaluate an improper list."))
+            }
+        },
+        _ => Err(String::from("Expecting a symbol, number, string or list."))
+    }
+}
+
+pub fn shen_eval_kl() -> KlClosure {
+    KlClosure::FeedMe(
+        Rc::new(
+            | code | {
+                match shen_element_to_token(&*code) {
+                    Ok(token) => KlClosure::Done(eval(&token, &Env::new()).map(Some)),
+                    Err(s) => KlClosure::Done(Err(shen_error(KlErrorKind::Type, "eval-kl", s.as_str())))
+                }
+            }
+        )
+    )
+}
+// eval-kl:1 ends here
+
+// [[file:../shen-rust.org::*Filling%20The%20Function%20Table][Filling\ The\ Function\ Table:1]]
+pub fn shen_fill_function_table() {
+    FUNCTION_TABLE.with(| function_table | {
+        let mut map = function_table.borrow_mut();
+        map.insert(KlSymbol::IF              ,shen_if());
+        map.insert(KlSymbol::AND             ,shen_and());
+        map.insert(KlSymbol::OR              ,shen_or());
+        map.insert(KlSymbol::COND            ,shen_cond());
+        map.insert(shen_intern_symbol(String::from("intern"))          ,shen_intern());
+        map.insert(shen_intern_symbol(String::from("pos"))             ,shen_pos());
+        map.insert(shen_intern_symbol(String::from("tlstr"))           ,shen_tlstr());
+        map.insert(shen_intern_symbol(String::from("cn"))              ,shen_cn());
+        map.insert(shen_intern_symbol(String::from("str"))             ,shen_str());
+        map.insert(shen_intern_symbol(String::from("string?"))         ,shen_stringp());
+        map.insert(shen_intern_symbol(String::from("n->string"))       ,shen_n_to_string());
+        map.insert(shen_intern_symbol(String::from("string->n"))       ,shen_string_to_n());
+        map.insert(shen_intern_symbol(String::from("simple-error"))    ,shen_simple_error());
+        map.insert(KlSymbol::TRAP_ERROR      ,shen_trap_error());
+        map.insert(shen_intern_symbol(String::from("error-to-string")) ,shen_error_to_string());
+        map.insert(shen_intern_symbol(String::from("set"))             ,shen_set());
+        map.insert(shen_intern_symbol(String::from("value"))           ,shen_value());
+        map.insert(shen_intern_symbol(String::from("cons"))            ,shen_cons());
+        map.insert(shen_intern_symbol(String::from("hd"))              ,shen_hd());
+        map.insert(shen_intern_symbol(String::from("tl"))              ,shen_tl());
+        map.insert(shen_intern_symbol(String::from("cons?"))           ,shen_consp());
+        map.insert(shen_intern_symbol(String::from("="))               ,shen_equal());
+        map.insert(shen_intern_symbol(String::from("absvector"))       ,shen_absvector());
+        map.insert(shen_intern_symbol(String::from("address->"))       ,shen_insert_at_address());
+        map.insert(shen_intern_symbol(String::from("<-address"))       ,shen_get_at_address());
+        map.insert(shen_intern_symbol(String::from("absvector?"))      ,shen_absvectorp());
+        map.insert(shen_intern_symbol(String::from("write-byte"))      ,shen_write_byte());
+        map.insert(shen_intern_symbol(String::from("read-byte"))       ,shen_read_byte());
+        map.insert(shen_intern_symbol(String::from("open"))            ,shen_open());
+        map.insert(shen_intern_symbol(String::from("close"))           ,shen_close());
+        map.insert(shen_intern_symbol(String::from("get-time"))        ,shen_get_time());
+        map.insert(shen_intern_symbol(String::from("+"))               ,shen_plus());
+        map.insert(shen_intern_symbol(String::from("*"))               ,shen_mul());
+        map.insert(shen_intern_symbol(String::from("-"))               ,shen_sub());
+        map.insert(shen_intern_symbol(String::from("/"))               ,shen_div());
+        map.insert(shen_intern_symbol(String::from(">"))               ,shen_ge());
+        map.insert(shen_intern_symbol(String::from("<"))               ,shen_le());
+        map.insert(shen_intern_symbol(String::from("<="))              ,shen_eq_le());
+        map.insert(shen_intern_symbol(String::from(">="))              ,shen_eq_ge());
+        map.insert(shen_intern_symbol(String::from("number?"))         ,shen_numberp());
+        map.insert(shen_intern_symbol(String::from("eval-kl"))         ,shen_eval_kl());
+        map.insert(shen_intern_symbol(String::from("shen-rust.error-trace")) ,shen_error_trace());
+        map.insert(shen_intern_symbol(String::from("shen-rust.error-kind")) ,shen_error_kind());
+        map.insert(shen_intern_symbol(String::from("shen-rust.error-origin")) ,shen_error_origin());
+        PRIMITIVE_NAMES.with(| names | *names.borrow_mut() = map.keys().cloned().collect());
+    })
+}
+// Filling\ The\ Function\ Table:1 ends here
+
+// [[file:../shen-rust.org::*Booting][Booting:1]]
+pub fn shen_set_global(name: &str, value: Rc<KlElement>) {
+    SYMBOL_TABLE.with(| symbol_table | {
+        let mut map = symbol_table.borrow_mut();
+        map.insert(shen_intern_symbol(String::from(name)), value);
+    })
+}
+
+pub fn shen_fill_global_table() {
+    let home_directory = match std::env::current_dir() {
+        Ok(directory) => format!("{}/", directory.display()),
+        Err(_) => String::from("")
+    };
+    shen_set_global("*stinput*"         ,Rc::new(KlElement::Stream(Rc::new(KlStream::Std(KlStdStream::Stdin)))));
+    shen_set_global("*stoutput*"        ,Rc::new(KlElement::Stream(Rc::new(KlStream::Std(KlStdStream::Stdout)))));
+    shen_set_global("*home-directory*"  ,Rc::new(KlElement::String(home_directory)));
+    shen_set_global("*language*"        ,Rc::new(KlElement::String(String::from("Rust"))));
+    shen_set_global("*implementation*"  ,Rc::new(KlElement::String(String::from("rustc"))));
+    shen_set_global("*release*"         ,Rc::new(KlElement::String(String::from(""))));
+    shen_set_global("*port*"            ,Rc::new(KlElement::String(String::from(env!("CARGO_PKG_VERSION")))));
+    shen_set_global("*porters*"         ,Rc::new(KlElement::String(String::from("Aditya Siram"))));
+    shen_set_global("*os*"              ,Rc::new(KlElement::String(String::from(std::env::consts::OS))));
+}
+
+pub fn load_kl_files(kl_directory: &Path, kl_files: &[&str]) -> Result<(), String> {
+    for f in kl_files {
+        let path = kl_directory.join(f);
+        let forms = read_kl_file_with_spans(&path).map_err(| e | format!("{}", e))?;
+        for (token, spans) in forms {
+            if let Some(name) = defun_name(&token) {
+                shen_record_defun_span(name, spans.span.clone());
+            }
+            match eval_spanned(&token, Some(&Rc::new(spans.clone())), &Env::new()) {
+                Ok(_) => (),
+                Err(e) => return Err(format!("{}: {}", spans.span, e))
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn boot_kl_files(kl_directory: &Path, kl_files: &[&str]) -> Result<(), String> {
+    shen_fill_function_table();
+    shen_fill_global_table();
+    load_kl_files(kl_directory, kl_files)
+}
+
+// What the main of a crate made by compile_kl_files runs: the tables
+// boot_kl_files fills, then the compiled modules' init in place of loading
+// KLambda, then shen.shen on a thread of its own.
+pub fn shen_run_compiled_kernel(stack_size: usize, init: fn()) -> Result<(), String> {
+    let toplevel = shen_spawn_with_stack(stack_size, move || {
+        shen_fill_function_table();
+        shen_fill_global_table();
+        init();
+        match shen_apply_arguments_to_function(shen_intern_symbol(String::from("shen.shen")), vec![]).map(shen_run_trampoline) {
+            Ok(KlClosure::Done(Err(e))) => Err(format!("{}", e)),
+            Err(e) => Err(e),
+            _ => Ok(())
+        }
+    });
+    match toplevel {
+        Ok(toplevel) => toplevel.join().unwrap_or(Err(String::from("the toplevel thread panicked"))),
+        Err(e) => Err(format!("could not start the toplevel thread: {}", e))
+    }
+}
+// Booting:1 ends here
+
+// [[file:../shen-rust.org::*Module%20Generation][Module\ Generation:1]]
+pub fn kl_module_name(file_name: &str) -> String {
+    let stem = Path::new(file_name).file_stem().and_then(| s | s.to_str()).unwrap_or(file_name);
+    let sanitized : String = stem.chars().map(| c | if c.is_alphanumeric() { c } else { '_' }).collect();
+    format!("kl_{}", sanitized)
+}
+
+pub fn rename_local_variables(bound: &Vec<KlSymbol>, token: &KlToken) -> KlToken {
+    match token {
+        &KlToken::Symbol(ref s) if bound.contains(s) => KlToken::Symbol(KlSymbol::new(&format!("kl_{}", s.as_str()))),
+        &KlToken::Cons(ref form) => {
+            match form.as_slice() {
+                &[ref kldefun @ KlToken::Symbol(ref defun), ref name, KlToken::Cons(ref args), ref body]
+                    if *defun == KlSymbol::DEFUN => {
+                        let new_bound = extract_arg_symbols(args);
+                        KlToken::Cons(vec![kldefun.clone(),
+                                           name.clone(),
+                                           KlToken::Cons(args.iter().map(| a | rename_local_variables(&new_bound, a)).collect()),
+                                           rename_local_variables(&new_bound, body)])
+                    },
+                &[ref kllambda @ KlToken::Symbol(ref lambda), ref arg @ KlToken::Symbol(_), ref body]
+                    if *lambda == KlSymbol::LAMBDA => {
+                        let mut new_bound = bound.clone();
+                        new_bound.extend(extract_arg_symbols(&[arg.clone()]));
+                        KlToken::Cons(vec![kllambda.clone(),
+                                           rename_local_variables(&new_bound, arg),
+                                           rename_local_variables(&new_bound, body)])
+                    },
+                &[ref kllet @ KlToken::Symbol(ref klet), ref var @ KlToken::Symbol(_), ref value, ref body]
+                    if *klet == KlSymbol::LET => {
+                        let mut new_bound = bound.clone();
+                        new_bound.extend(extract_arg_symbols(&[var.clone()]));
+                        KlToken::Cons(vec![kllet.clone(),
+                                           rename_local_variables(&new_bound, var),
+                                           rename_local_variables(bound, value),
+                                           rename_local_variables(&new_bound, body)])
+                    },
+                _ => KlToken::Cons(form.iter().map(| t | rename_local_variables(bound, t)).collect())
+            }
+        },
+        _ => token.clone()
+    }
+}
+
+pub fn generate_module(forms: &Vec<(KlToken, KlSpanTree)>) -> Result<String, String> {
+    let mut result : Vec<String> = Vec::new();
+    let mut body : Vec<String> = Vec::new();
+    GENERATED_SYMBOLS.with(| symbols | symbols.borrow_mut().clear());
+    for &(ref written, ref spans) in forms {
+        let token = &rename_local_variables(&vec![], written);
+        let spans = Rc::new(spans.clone());
+        // Renaming leaves the shape of a form alone, so a form that fails
+        // fails as written too, and is described as it was written.
+        let generated = match generate(false, vec![], token, Some(&spans)) {
+            Ok(generated) => intersperse(generated, String::from("\n")),
+            Err(e) => return Err(generate(false, vec![], written, Some(&spans)).err().unwrap_or(e))
+        };
+        let span = &spans.span;
+        body.push(format!("// {}:{}", span.file.as_ref().map(| f | f.as_str()).unwrap_or("<input>"), span.line));
+        match defun_name(token) {
+            Some(name) => {
+                body.push(format!("shen_record_defun_span({}, KlSpan::new({:?}, {}, {}));",
+                                  generate_symbol(name), span.file.as_ref().map(| f | f.as_str()), span.line, span.column));
+                body.push(format!("{};", generated))
+            },
+            None => body.push(format!("let _ = shen_run_trampoline({});", generated))
+        }
+    }
+    let mut symbols : Vec<KlSymbol> = GENERATED_SYMBOLS.with(| symbols | symbols.borrow_mut().drain(..).collect());
+    symbols.sort_by(| a, b | a.as_str().cmp(&b.as_str()));
+    symbols.dedup();
+    result.push(String::from("#![allow(unused_variables, unused_mut, non_snake_case)]"));
+    result.push(String::from("use std::rc::Rc;"));
+    result.push(String::from("use shen_rust::*;"));
+    result.push(String::new());
+    result.push(String::from("pub fn init() {"));
+    for s in symbols {
+        result.push(format!("let {} = KlSymbol::new({:?});", generate_symbol_name(s), s.as_str()));
+    }
+    result.extend(body);
+    result.push(String::from("}"));
+    Ok(intersperse(result, String::from("\n")))
+}
+
+pub fn defun_name(token: &KlToken) -> Option<KlSymbol> {
+    match token {
+        &KlToken::Cons(ref defun) => {
+            match defun.as_slice() {
+                &[KlToken::Symbol(kldefun), KlToken::Symbol(name), KlToken::Cons(_), _]
+                    if kldefun == KlSymbol::DEFUN => Some(name),
+                _ => None
+            }
+        },
+        _ => None
+    }
+}
+// Module\ Generation:1 ends here
+
+// [[file:../shen-rust.org::*Crate%20Generation][Crate\ Generation:1]]
+// Where a generated crate gets shen-rust from: a released version, or a
+// checkout on disk. The runtime a generated crate calls into is newer than
+// any release, so by default it is the checkout this compiler was built from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KlRuntimeDependency {
+    Version(String),
+    Path(PathBuf)
+}
+
+impl KlRuntimeDependency {
+    pub fn this_checkout() -> KlRuntimeDependency {
+        KlRuntimeDependency::Path(PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+    }
+}
+
+pub fn generate_crate_manifest(crate_name: &str, runtime: &KlRuntimeDependency) -> String {
+    let mut result : Vec<String> = Vec::new();
+    result.push(String::from("[package]"));
+    result.push(format!("name = \"{}\"", crate_name));
+    result.push(String::from("version = \"0.0.1\""));
+    result.push(String::new());
+    result.push(String::from("[dependencies]"));
+    match runtime {
+        &KlRuntimeDependency::Version(ref version) => result.push(format!("shen-rust = \"={}\"", version)),
+        &KlRuntimeDependency::Path(ref path) => result.push(format!("shen-rust = {{ path = {:?} }}", path.display().to_string()))
+    }
+    intersperse(result, String::from("\n")) + "\n"
+}
+
+pub fn generate_crate_main(module_names: &Vec<String>) -> String {
+    let mut result : Vec<String> = Vec::new();
+    result.push(String::from("#![recursion_limit = \"4096\"]"));
+    result.push(String::from("extern crate shen_rust;"));
+    result.push(String::from("use shen_rust::*;"));
+    result.push(String::from("use std::io::prelude::*;"));
+    result.push(String::from("use std::io;"));
+    result.push(String::from("use std::process;"));
+    result.push(String::new());
+    for m in module_names {
+        result.push(format!("mod {};", m));
+    }
+    result.push(String::new());
+    result.push(String::from("pub fn init() {"));
+    result.push(String::from("    // shen.shen reads on forever once stdin is over."));
+    result.push(String::from("    shen_on_end_of_stdin(Box::new(|| {"));
+    result.push(String::from("        println!(\"\");"));
+    result.push(String::from("        process::exit(0)"));
+    result.push(String::from("    }));"));
+    for m in module_names {
+        result.push(format!("    {}::init();", m));
+    }
+    result.push(String::from("}"));
+    result.push(String::new());
+    result.push(String::from("const STACK_SIZE : usize = 1 << 30;"));
+    result.push(String::new());
+    result.push(String::from("fn main() {"));
+    result.push(String::from("    if let Err(e) = shen_run_compiled_kernel(STACK_SIZE, init) {"));
+    result.push(String::from("        writeln!(io::stderr(), \"error: {}\", e).unwrap();"));
+    result.push(String::from("        process::exit(1)"));
+    result.push(String::from("    }"));
+    result.push(String::from("}"));
+    intersperse(result, String::from("\n")) + "\n"
+}
+
+pub fn read_kl_file_with_spans(path: &Path) -> io::Result<Vec<(KlToken, KlSpanTree)>> {
+    let in_path = | e : io::Error | io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+    let mut file = File::open(path).map_err(&in_path)?;
+    let mut buffer : Vec<u8> = Vec::new();
+    file.read_to_end(&mut buffer).map_err(&in_path)?;
+    parse_kl_with_spans(&buffer, Some(&path.display().to_string())).map_err(| e | {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    })
+}
+
+pub fn read_kl_file(path: &Path) -> io::Result<Vec<KlToken>> {
+    read_kl_file_with_spans(path).map(| forms | forms.into_iter().map(| (form, _) | form).collect())
+}
+
+pub fn compile_kl_files(kl_directory: &Path, kl_files: &[&str], out_directory: &Path, runtime: &KlRuntimeDependency) -> io::Result<()> {
+    let src_directory = out_directory.join("src");
+    std::fs::create_dir_all(&src_directory)?;
+    let mut module_names = Vec::new();
+    for f in kl_files {
+        let tokens = read_kl_file_with_spans(&kl_directory.join(f))?;
+        let generated = generate_module(&tokens).map_err(| e | io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let module_name = kl_module_name(f);
+        let mut module = File::create(src_directory.join(format!("{}.rs", module_name)))?;
+        module.write_all(generated.as_bytes())?;
+        module_names.push(module_name);
+    }
+    let mut main = File::create(src_directory.join("main.rs"))?;
+    main.write_all(generate_crate_main(&module_names).as_bytes())?;
+    let mut manifest = File::create(out_directory.join("Cargo.toml"))?;
+    manifest.write_all(generate_crate_manifest("shen-kernel", runtime).as_bytes())?;
+    Ok(())
+}
+// Crate\ Generation:1 ends here
+
+// [[file:../shen-rust.org::*Tests][Tests:1]]
+// Generated modules refer to the runtime as shen_rust.
+#[cfg(test)]
+extern crate self as shen_rust;
+
+// The modules generate_module makes of the KLambda fixtures, regenerated with
+//   shen-rust parse tests/fixtures/NAME.kl --dump rust > tests/fixtures/NAME.rs
+#[cfg(test)]
+#[path = "../tests/fixtures/tail_calls.rs"]
+mod tail_calls;
+
+#[cfg(test)]
+#[path = "../tests/fixtures/globals.rs"]
+mod globals;
+
+#[cfg(test)]
+#[path = "../tests/fixtures/errors.rs"]
+mod errors;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Evaluates the forms of kl in turn and prints the value of the last one,
+    // or the message of the error it raised.
+    fn eval_kl(kl: &str) -> String {
+        shen_fill_function_table();
+        let mut printed = String::new();
+        for form in parse_kl(kl.as_bytes()).unwrap() {
+            printed = match eval(&form, &Env::new()) {
+                Ok(value) => format!("{}", value),
+                Err(e) => format!("error: {}", e.message())
+            };
+        }
+        printed
+    }
+
+    #[test]
+    fn eval_kl_defines_functions_and_evaluates_atoms_to_themselves() {
+        let defun = "(eval-kl (cons defun (cons evaluated-f (cons (cons X ()) (cons (cons + (cons X (cons 1 ()))) ())))))";
+        assert_eq!(eval_kl(defun), "evaluated-f");
+        assert!(FUNCTION_TABLE.with(| table | table.borrow().contains_key(&KlSymbol::intern("evaluated-f"))));
+        assert_eq!(eval_kl("(evaluated-f 1)"), "2");
+        let atoms = [("(eval-kl 5)", "5"), ("(eval-kl \"(+ 1 2)\")", "\"(+ 1 2)\""), ("(eval-kl foo)", "foo")];
+        for &(kl, expected) in atoms.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+        let cases = [
+            ("(eval-kl (cons + (cons 1 2)))", "(type eval-kl \"eval-kl: Cannot evaluate an improper list.\")"),
+            ("(eval-kl (absvector 1))", "(type eval-kl \"eval-kl: Expecting a symbol, number, string or list.\")"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            let caught = eval_kl(&format!(
+                "(trap-error {} (lambda E (cons (shen-rust.error-kind E) (cons (shen-rust.error-origin E) (cons (error-to-string E) ())))))", kl));
+            assert_eq!(caught, expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn absvector_bounds() {
+        let cases = [
+            ("(absvector 0)", "<absvector>"),
+            ("(absvector 2)", "<absvector shen.fail! shen.fail!>"),
+            ("(absvector -1)", "error: absvector: Expecting a non-negative integer."),
+            ("(absvector 9223372036854775807)", "error: absvector: Cannot allocate a vector of 9223372036854775807 elements."),
+            ("(<-address (absvector 2) 1)", "shen.fail!"),
+            ("(<-address (absvector 2) 2)", "error: <-address: Expecting an index less than the vector length."),
+            ("(<-address (absvector 0) 0)", "error: <-address: Expecting an index less than the vector length."),
+            ("(address-> (absvector 2) 1 a)", "<absvector shen.fail! a>"),
+            ("(address-> (absvector 2) 2 a)", "error: address->: Expecting an index less than the vector length."),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn big_integers_against_floats() {
+        let cases = [
+            ("(> 1e400 1.5)", "true"),
+            ("(< 1.5 1e400)", "true"),
+            ("(< 1e400 1.5)", "false"),
+            ("(> -1e400 -1.5)", "false"),
+            ("(>= 1e400 1e400)", "true"),
+            ("(> 9007199254740993 9007199254740992.0)", "true"),
+            ("(<= 3 3.0)", "true"),
+            ("(< 2.5 3)", "true"),
+            ("(+ 0.5 1e400)", "inf"),
+            ("(* 1.0 (* 1e300 1e300))", "inf"),
+            ("(* -1.0 (* 1e300 1e300))", "-inf"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn division_and_equality_of_big_integers() {
+        let cases = [
+            ("(/ 1e400 3)", "inf"),
+            ("(/ -1e400 3)", "-inf"),
+            ("(/ 1e400 (* 3 1e399))", "3.3333333333333335"),
+            ("(/ (* 7 1e400) 1e400)", "7"),
+            ("(/ 1 (* 3 1e400))", "0.0"),
+            ("(/ 1e308 (* 3 1e300))", "33333333.333333332"),
+            ("(/ 7 2)", "3.5"),
+            ("(/ 1e400 0)", "error: /: division by zero."),
+            ("(= 9007199254740993 9007199254740992.0)", "false"),
+            ("(= 9007199254740992 9007199254740992.0)", "true"),
+            ("(= 1e400 (* 1.0 1e400))", "false"),
+            ("(= 1 1.0)", "true"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn numeric_literals_end_at_a_delimiter() {
+        let symbol = | name: &str | KlToken::Symbol(shen_intern_symbol(String::from(name)));
+        let cases = vec![
+            ("(f 1.2.3)", vec![symbol("f"), symbol("1.2.3")]),
+            ("(f 1e-2.5)", vec![symbol("f"), symbol("1e-2.5")]),
+            ("(f 5.)", vec![symbol("f"), symbol("5.")]),
+            ("(f 1e)", vec![symbol("f"), symbol("1e")]),
+            ("(f 1.e2)", vec![symbol("f"), symbol("1.e2")]),
+            ("(1-)", vec![symbol("1-")]),
+            ("(f -1-)", vec![symbol("f"), symbol("-1-")]),
+            ("(f 1.5)", vec![symbol("f"), KlToken::Number(KlNumber::Float(1.5))]),
+            ("(f -3 1e3)", vec![symbol("f"), KlToken::Number(KlNumber::Int(-3)), KlToken::Number(KlNumber::Int(1000))]),
+            ("(f 1(g))", vec![symbol("f"), KlToken::Number(KlNumber::Int(1)), KlToken::Cons(vec![symbol("g")])]),
+            ("(f\t2\n)", vec![symbol("f"), KlToken::Number(KlNumber::Int(2))]),
+        ];
+        for (kl, expected) in cases {
+            assert_eq!(parse_kl(kl.as_bytes()), Ok(vec![KlToken::Cons(expected)]), "{}", kl);
+        }
+        let numbers = [
+            (".5", KlNumber::Float(0.5), "0.5"),
+            ("-.5", KlNumber::Float(-0.5), "-0.5"),
+            ("1e10", KlNumber::Int(10000000000), "10000000000"),
+            ("-2.5e-3", KlNumber::Float(-0.0025), "-0.0025"),
+            ("--3", KlNumber::Int(3), "3"),
+            ("9223372036854775807", KlNumber::Int(9223372036854775807), "9223372036854775807"),
+            ("9223372036854775808", shen_big_number("9223372036854775808"), "9223372036854775808"),
+            ("-9223372036854775809", shen_big_number("-9223372036854775809"), "-9223372036854775809"),
+            ("1e19", shen_big_number("10000000000000000000"), "10000000000000000000"),
+        ];
+        for &(kl, ref number, value) in numbers.iter() {
+            assert_eq!(parse_kl(format!("(f {})", kl).as_bytes()),
+                       Ok(vec![KlToken::Cons(vec![symbol("f"), KlToken::Number(number.clone())])]), "{}", kl);
+            assert_eq!(eval_kl(&format!("(do 0 {})", kl)), value, "{}", kl);
+        }
+        let out_of_range = parse_kl(b"(f 1e99999999999)").unwrap_err();
+        assert_eq!(out_of_range.message, "exponent of 1e99999999999 is out of range");
+    }
+
+    #[test]
+    fn integer_exponents_are_bounded() {
+        let digits = match parse_kl(b"(1e4096)") {
+            Ok(ref forms) => match &forms[..] {
+                &[KlToken::Cons(ref elements)] => match &elements[..] {
+                    &[KlToken::Number(KlNumber::Big(ref b))] => format!("{}", b).len(),
+                    _ => 0
+                },
+                _ => 0
+            },
+            _ => 0
+        };
+        assert_eq!(digits, 4097);
+        let too_big = parse_kl(b"(f 1e2000000000)").unwrap_err();
+        assert_eq!((too_big.offset, too_big.message.as_str()), (3, "exponent of 1e2000000000 is out of range"));
+        assert!(parse_kl(b"(f 1e4097)").is_err());
+        assert_eq!(parse_kl(b"(1e-2000000000)"), Ok(vec![KlToken::Cons(vec![KlToken::Number(KlNumber::Float(0.0))])]));
+    }
+
+    #[test]
+    fn strings_read_their_escapes() {
+        let read = parse_kl(b"(f \"a\\tb\\rc\\u0041d c#66; c#x c#9; \\u00e9\")");
+        assert_eq!(read, Ok(vec![KlToken::Cons(vec![KlToken::Symbol(KlSymbol::intern("f")),
+                                                     KlToken::String(String::from("a\tb\rcAd B c#x \t \u{e9}"))])]));
+        let mut reader = KlReader::new();
+        reader.feed(b"(f \"abc\\");
+        assert_eq!(reader.next_form(), Ok(None));
+        reader.feed(b"n\")");
+        assert_eq!(reader.next_form(), Ok(Some(KlToken::Cons(vec![KlToken::Symbol(KlSymbol::intern("f")),
+                                                                  KlToken::String(String::from("abc\n"))]))));
+    }
+
+    #[test]
+    fn strings_with_bad_escapes_or_bytes_are_rejected() {
+        let cases : Vec<(&[u8], usize, &str)> = vec![
+            (b"(f \"a\xffb\")", 3, "invalid UTF-8 in string at byte 1"),
+            (b"(f \"abc\\", 3, "unterminated string"),
+            (b"(f \"abc", 3, "unterminated string"),
+            (b"(f \"a\\u00\")", 3, "\\u expects four hex digits naming a character"),
+            (b"(f \"a\\u12G4\")", 3, "\\u expects four hex digits naming a character"),
+            (b"(f \"a\\u+041\")", 3, "\\u expects four hex digits naming a character"),
+            (b"(f \"a\\q\")", 3, "unknown escape '\\q' in string"),
+            (b"(f \"c#1114112;\")", 3, "c#1114112; does not name a character"),
+            (b"(f \"ok\") (g \"a\\u00\")", 12, "\\u expects four hex digits naming a character"),
+        ];
+        for (kl, offset, message) in cases {
+            let error = parse_kl(kl).unwrap_err();
+            assert_eq!((error.offset, error.message.as_str()), (offset, message), "{}", String::from_utf8_lossy(kl));
+        }
+    }
+
+    #[test]
+    fn lists_nested_too_deep_are_rejected() {
+        let nested = | depth : usize, closed : bool | {
+            let mut kl = String::from("(f) ");
+            kl.push_str(&"(".repeat(depth));
+            if closed {
+                kl.push_str(&")".repeat(depth));
+            }
+            kl
+        };
+        let deepest = parse_kl(nested(MAX_FORM_DEPTH, true).as_bytes()).unwrap();
+        assert_eq!(deepest.len(), 2);
+        for &(depth, closed) in [(MAX_FORM_DEPTH + 1, true), (200000, true), (200000, false)].iter() {
+            let kl = nested(depth, closed);
+            let error = parse_kl(kl.as_bytes()).unwrap_err();
+            assert_eq!((error.offset, error.message.as_str()), (4 + MAX_FORM_DEPTH, format!("lists nested more than {} deep", MAX_FORM_DEPTH).as_str()));
+            assert_eq!(parse_kl_with_spans(kl.as_bytes(), None).unwrap_err(), error);
+            let mut reader = KlReader::new();
+            reader.feed(kl.as_bytes());
+            reader.feed(b" (g)");
+            reader.close();
+            assert_eq!(reader.next_form(), Ok(Some(KlToken::Cons(vec![KlToken::Symbol(KlSymbol::intern("f"))]))));
+            assert_eq!(reader.next_form().unwrap_err().offset, 4 + MAX_FORM_DEPTH);
+            if closed {
+                assert_eq!(reader.next_form(), Ok(Some(KlToken::Cons(vec![KlToken::Symbol(KlSymbol::intern("g"))]))));
+            }
+        }
+        drop(deepest);
+    }
+
+    #[test]
+    fn symbols_take_shen_characters_and_comments_sit_between_elements() {
+        let symbol = | name: &str | KlToken::Symbol(KlSymbol::intern(name));
+        assert_eq!(parse_kl(b"(f a^b x|y ^ |)"), Ok(vec![KlToken::Cons(vec![symbol("f"), symbol("a^b"), symbol("x|y"), symbol("^"), symbol("|")])]));
+        let commented = parse_kl(b"(f 1 \\\\ comment (g)\n   2 \\* block (h) *\\ 3)");
+        assert_eq!(commented, Ok(vec![KlToken::Cons(vec![symbol("f"), KlToken::Number(KlNumber::Int(1)),
+                                                          KlToken::Number(KlNumber::Int(2)), KlToken::Number(KlNumber::Int(3))])]));
+    }
+
+    #[test]
+    fn reader_skips_a_bare_toplevel_atom() {
+        let mut reader = KlReader::new();
+        reader.feed(b"foo (f 1)\n");
+        reader.close();
+        let error = reader.next_form().unwrap_err();
+        assert_eq!((error.line, error.column, error.message.as_str()), (1, 1, "expecting '(' or a string at toplevel"));
+        assert_eq!(format!("{}", reader.next_form().unwrap().unwrap()), "(f 1)");
+        assert_eq!(reader.next_form(), Ok(None));
+    }
+
+    #[test]
+    fn reader_waits_for_the_rest_of_a_token() {
+        let mut reader = KlReader::new();
+        reader.feed(b"(f 1) fo");
+        assert_eq!(format!("{}", reader.next_form().unwrap().unwrap()), "(f 1)");
+        assert_eq!(reader.next_form(), Ok(None));
+        assert!(reader.needs_more_input());
+        reader.feed(b"o (g 1");
+        let error = reader.next_form().unwrap_err();
+        assert_eq!((error.column, error.message.as_str()), (7, "expecting '(' or a string at toplevel"));
+        assert_eq!(reader.next_form(), Ok(None));
+        reader.feed(b"2)");
+        assert_eq!(format!("{}", reader.next_form().unwrap().unwrap()), "(g 12)");
+        reader.close();
+        assert_eq!(reader.next_form(), Ok(None));
+        assert!(!reader.needs_more_input());
+    }
+
+    #[test]
+    fn spans_point_at_the_start_of_each_form() {
+        let kl = b"\\\\ comment\n(defun f () 1)\n  \"doc\" (f)\n\n \\* block\n *\\ (g (h))";
+        let forms = parse_kl_with_spans(kl, Some("t.kl")).unwrap();
+        let spans : Vec<String> = forms.iter().map(| &(_, ref spans) | format!("{}", spans.span)).collect();
+        assert_eq!(spans, vec!["t.kl:2:1", "t.kl:3:9", "t.kl:6:5"]);
+        let mut reader = KlReader::for_file("t.kl");
+        reader.feed(kl);
+        reader.close();
+        let mut read = Vec::new();
+        while let Some((_, spans)) = reader.next_form_with_spans().unwrap() {
+            read.push(spans);
+        }
+        assert_eq!(read, forms.into_iter().map(| (_, spans) | spans).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn spans_point_at_the_start_of_each_subform() {
+        let kl = b"(defun f (X)\n  (g \"a b\" \\\\ comment\n     1.5e3 (h X)))";
+        let forms = parse_kl_with_spans(kl, Some("t.kl")).unwrap();
+        let spans = Rc::new(forms[0].1.clone());
+        let at = | path: &[usize] | shen_spans_at(Some(&spans), path).map(| s | format!("{}", s.span));
+        assert_eq!(at(&[]), Some(String::from("t.kl:1:1")));
+        assert_eq!(at(&[2, 0]), Some(String::from("t.kl:1:11")));
+        assert_eq!(at(&[3]), Some(String::from("t.kl:2:3")));
+        assert_eq!(at(&[3, 1]), Some(String::from("t.kl:2:6")));
+        assert_eq!(at(&[3, 2]), Some(String::from("t.kl:3:6")));
+        assert_eq!(at(&[3, 3, 1]), Some(String::from("t.kl:3:15")));
+        assert_eq!(at(&[3, 4]), None);
+    }
+
+    #[test]
+    fn symbols_that_mangle_alike_stay_apart() {
+        let pairs = [("foo-bar", "foo__Dash__bar"), ("if", "shen_if"), ("*x*", "__Star__x__Star__"), ("a_b", "a__U5F__b")];
+        for &(a, b) in pairs.iter() {
+            let (mangled_a, mangled_b) = (shen_rename_symbol(String::from(a)), shen_rename_symbol(String::from(b)));
+            assert!(mangled_a != mangled_b, "{} and {} both mangle to {}", a, b, mangled_a);
+            for &(name, ref mangled) in [(a, &mangled_a), (b, &mangled_b)].iter() {
+                assert_eq!(shen_unrename_symbol((*mangled).clone()), name);
+                assert_eq!(shen_symbol_name(KlSymbol::intern(name)), name);
+                assert_eq!(KlSymbol::intern(name), KlSymbol::new(mangled));
+            }
+            assert!(KlSymbol::intern(a) != KlSymbol::intern(b), "{} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn preinterned_symbols_keep_their_ids_on_every_thread() {
+        fn check() {
+            let constants = [KlSymbol::TRUE, KlSymbol::FALSE, KlSymbol::FAIL, KlSymbol::DEFUN, KlSymbol::LAMBDA,
+                             KlSymbol::LET, KlSymbol::IF, KlSymbol::COND, KlSymbol::DO, KlSymbol::AND, KlSymbol::OR,
+                             KlSymbol::FREEZE, KlSymbol::TRAP_ERROR, KlSymbol::IN, KlSymbol::RUN, KlSymbol::REAL];
+            assert_eq!(constants.len(), PREINTERNED_SYMBOLS.len());
+            let fresh = KlSymbol::intern("not-preinterned");
+            for (name, constant) in PREINTERNED_SYMBOLS.iter().zip(constants.iter()) {
+                assert!(KlSymbol::intern(name) == *constant, "{} does not have its preinterned id", name);
+                assert_eq!(shen_symbol_name(*constant), *name);
+            }
+            assert!(KlSymbol::intern("not-preinterned") == fresh);
+            assert!(shen_intern_symbol(String::from("not-preinterned")) == fresh);
+        }
+        check();
+        thread::spawn(check).join().unwrap();
+    }
+
+    #[test]
+    fn generated_manifests_name_the_runtime_they_were_given() {
+        let released = generate_crate_manifest("k", &KlRuntimeDependency::Version(String::from("1.2.3")));
+        assert!(released.contains("\n[dependencies]\nshen-rust = \"=1.2.3\"\n"));
+        let checkout = generate_crate_manifest("k", &KlRuntimeDependency::Path(PathBuf::from("/src/shen-rust")));
+        assert!(checkout.contains("\n[dependencies]\nshen-rust = { path = \"/src/shen-rust\" }\n"));
+        let this_checkout = format!("\n[dependencies]\nshen-rust = {{ path = {:?} }}\n", env!("CARGO_MANIFEST_DIR"));
+        assert!(generate_crate_manifest("k", &KlRuntimeDependency::this_checkout()).contains(&this_checkout));
+    }
+
+    #[test]
+    fn printed_forms_read_back_as_the_same_forms() {
+        let sources = [
+            "(f \"tab\\tquote\\\"backslash\\\\ newline\\n cr\\r\" \"c#65;\" \"\\u0063#65;\" \"\\u0001\" \"\")",
+            "(f 1.0 -2.0 1e20 0.5 -0.0025 1.5e300 5e-324 3 -3 9223372036854775808)",
+            "(foo-bar foo__Dash__bar shen.x if shen_if *stoutput* <-address @p ! = 1- -1- 1.2.3)",
+            "(f () (()) (g (h (i ())) ()) j)",
+            "(defun f (X Y) (let Z (cons X ()) (cond ((= Z ()) \"a b\") (true (f (+ X 1.0) \"\")))))",
+        ];
+        for source in sources.iter() {
+            let forms = parse_kl(source.as_bytes()).unwrap();
+            let printed = intersperse(forms.iter().map(| f | format!("{}", f)).collect(), String::from("\n"));
+            assert_eq!(parse_kl(printed.as_bytes()), Ok(forms), "{} printed as {}", source, printed);
+        }
+        assert_eq!(parse_kl(format!("{}", parse_kl(b"(f 1.0)").unwrap()[0]).as_bytes()),
+                   Ok(vec![KlToken::Cons(vec![KlToken::Symbol(KlSymbol::intern("f")), KlToken::Number(KlNumber::Float(1.0))])]));
+    }
+
+    #[test]
+    fn a_cons_with_a_non_list_tail_is_a_dotted_pair() {
+        let cases = [
+            ("(hd (cons 1 2))", "1"),
+            ("(tl (cons 1 2))", "2"),
+            ("(cons? (cons 1 2))", "true"),
+            ("(cons 1 2)", "(1 | 2)"),
+            ("(cons 1 (cons 2 3))", "(1 2 | 3)"),
+            ("(= (cons 1 2) (cons 1 2))", "true"),
+            ("(= (cons 1 2) (cons 1 (cons 2 ())))", "false"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn a_global_set_to_the_empty_list_is_not_an_unset_one() {
+        let cases = [
+            ("(set empty-global ())", "()"),
+            ("(do (set empty-global ()) (value empty-global))", "()"),
+            ("(do (set empty-global ()) (= (value empty-global) ()))", "true"),
+            ("(do (set empty-global ()) (cons? (value empty-global)))", "false"),
+            ("(value never-set-global)", "error: value: variable never-set-global is unbound"),
+            ("(trap-error (= (value never-set-global) ()) (lambda E unbound))", "unbound"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(eval_kl(kl), expected, "{}", kl);
+        }
+    }
+
+    #[test]
+    fn printer_survives_cycles_and_marks_recur_unreadable() {
+        assert_eq!(eval_kl("(let V (absvector 2) (address-> (address-> V 0 V) 1 (cons V ())))"), "<absvector <...> (<...>)>");
+        assert_eq!(eval_kl("(let V (absvector 1) (let W (absvector 1) (address-> V 0 (address-> W 0 V))))"), "<absvector <absvector <...>>>");
+        assert_eq!(eval_kl("(let V (absvector 1) (cons V V))"), "(<absvector shen.fail!> | <absvector shen.fail!>)");
+        let recur = KlToken::Recur(vec![KlToken::Number(KlNumber::Int(1)), KlToken::Symbol(shen_intern_symbol(String::from("x")))]);
+        assert_eq!(format!("{}", recur), "<recur 1 x>");
+    }
+
+    // A list nested depth deep through the heads of its conses: (((... 0))).
+    fn nested_list(depth: usize) -> Rc<KlElement> {
+        let mut list = Rc::new(KlElement::Number(KlNumber::Int(0)));
+        for _ in 0..depth {
+            list = Rc::new(KlElement::Cons(list, shen_nil()));
+        }
+        list
+    }
+
+    #[test]
+    fn deep_structures_drop_compare_and_print_without_overflowing() {
+        let finished = shen_spawn_with_stack(8 << 20, || {
+            shen_fill_function_table();
+            let (a, b) = (nested_list(1000000), nested_list(1000000));
+            assert!(shen_vector_equal(vec![(a.clone(), b.clone())]));
+            assert!(!shen_vector_equal(vec![(a.clone(), nested_list(999999))]));
+            assert!(format!("{}", a).contains("..."));
+            let vector = Rc::new(KlElement::Vector(Rc::new(UniqueVector { vector: RefCell::new(vec![a]) })));
+            drop(vector);
+            drop(b);
+        });
+        finished.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn deeply_nested_forms_fail_to_evaluate_with_a_stack_error() {
+        let finished = shen_spawn_with_stack(8 << 20, || {
+            shen_fill_function_table();
+            let plus = shen_intern_symbol(String::from("+"));
+            let mut form = KlToken::Number(KlNumber::Int(0));
+            for _ in 0..20000 {
+                form = KlToken::Cons(vec![KlToken::Symbol(plus), KlToken::Number(KlNumber::Int(1)), form]);
+            }
+            let error = eval(&form, &Env::new()).unwrap_err();
+            assert_eq!((error.kind, error.message.as_str()), (KlErrorKind::StackOverflow, "stack depth exceeded."));
+        });
+        finished.unwrap().join().unwrap();
+    }
+
+    #[test]
+    fn vectors_that_hold_themselves_compare_equal() {
+        let kl = "(defun cyclic (X) (let V (absvector 2) (address-> (address-> V 0 V) 1 X)))";
+        assert_eq!(eval_kl(&format!("{} (= (cyclic 1) (cyclic 1))", kl)), "true");
+        assert_eq!(eval_kl(&format!("{} (= (cyclic 1) (cyclic 2))", kl)), "false");
+    }
+
+    #[test]
+    fn only_uncaught_errors_carry_their_trace() {
+        let kl = "(defun outer (X) (+ 1 (inner X))) (defun inner (X) (+ 1 (simple-error \"boom\")))";
+        assert_eq!(eval_kl(&format!("{} (trap-error (outer 1) (lambda E (error-to-string E)))", kl)), "\"boom\"");
+        let uncaught = eval(&parse_kl(b"(outer 1)").unwrap()[0], &Env::new()).unwrap_err();
+        assert!(format!("{}", uncaught).starts_with("boom\n    in inner"), "{}", uncaught);
+    }
+
+    #[test]
+    fn caught_errors_know_their_kind_and_origin() {
+        let caught = | kl : &str | eval_kl(&format!(
+            "(trap-error {} (lambda E (cons (shen-rust.error-kind E) (cons (shen-rust.error-origin E) (cons (error-to-string E) ())))))", kl));
+        let cases = [
+            ("(simple-error \"boom\")", "(user () \"boom\")"),
+            ("(+ 1 a)", "(type + \"+: expecting two numbers.\")"),
+            ("(tl 1)", "(type tl \"tl: Expecting a non-empty list.\")"),
+            ("(open \"/no/such/file\" in)", "(io open \"open: Could not open file.\")"),
+            ("(no-such-function 1)", "(undefined () \"Could not find function: no-such-function\")"),
+            ("(value no-such-global)", "(runtime value \"value: variable no-such-global is unbound\")"),
+            ("(error-to-string \"boom\")", "(type error-to-string \"error-to-string: expecting an exception.\")"),
+            ("((lambda X X) 1 2)", "(arity () \"Given a fully saturated closure or thunk\")"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            assert_eq!(caught(kl), expected, "{}", kl);
+        }
+        assert_eq!(eval_kl("(error-to-string \"boom\")"), "error: error-to-string: expecting an exception.");
+        assert_eq!(eval_kl("(shen-rust.error-kind (cons 1 2))"), "error: shen-rust.error-kind: expecting an exception.");
+    }
+
+    const TAIL_CALLS_KL : &'static str = include_str!("../tests/fixtures/tail_calls.kl");
+    const ERRORS_KL : &'static str = include_str!("../tests/fixtures/errors.kl");
+
+    // Enough calls that any loop keeping a frame per call runs out of the
+    // stack below.
+    const LOOP_LENGTH : i64 = 20000;
+    const SMALL_STACK_SIZE : usize = 1 << 20;
+
+    #[test]
+    fn the_fixture_modules_are_what_generate_module_makes() {
+        let fixtures = [("tail_calls", TAIL_CALLS_KL, include_str!("../tests/fixtures/tail_calls.rs")),
+                        ("globals", include_str!("../tests/fixtures/globals.kl"), include_str!("../tests/fixtures/globals.rs")),
+                        ("errors", ERRORS_KL, include_str!("../tests/fixtures/errors.rs"))];
+        for &(name, kl, rust) in &fixtures {
+            let forms = parse_kl_with_spans(kl.as_bytes(), Some(&format!("tests/fixtures/{}.kl", name))).unwrap();
+            assert!(generate_module(&forms).unwrap() + "\n" == rust, "tests/fixtures/{}.rs is out of date", name);
+        }
+    }
+
+    #[test]
+    fn forms_that_apply_a_non_function_do_not_compile() {
+        let cases = [
+            ("(1 2)", "t.kl:1:1: cannot apply 1 in (1 2)"),
+            ("(defun f (X)\n  (do X\n      (\"f\" X)))", "t.kl:3:7: cannot apply \"f\" in (\"f\" X)"),
+            ("(lambda X (if X (2.5) X))", "t.kl:1:17: cannot apply 2.5 in (2.5)"),
+        ];
+        for &(kl, expected) in cases.iter() {
+            let forms = parse_kl_with_spans(kl.as_bytes(), Some("t.kl")).unwrap();
+            assert_eq!(generate_module(&forms), Err(String::from(expected)), "{}", kl);
+        }
+    }
+
+    #[test]
+    fn a_compiled_kernel_starts_with_the_globals_set() {
+        let main = generate_crate_main(&vec![String::from("kl_globals")]);
+        assert!(main.contains("shen_run_compiled_kernel(STACK_SIZE, init)"));
+        assert!(main.contains("shen_on_end_of_stdin("));
+        assert_eq!(shen_run_compiled_kernel(8 << 20, globals::init), Ok(()));
+    }
+
+    #[test]
+    fn traces_name_the_line_each_function_raised_its_error_from() {
+        let expected = "boom\n    in errors.inner (tests/fixtures/errors.kl:12:10)\n    in errors.outer (tests/fixtures/errors.kl:7:6)";
+        let evaluated = shen_spawn_with_stack(SMALL_STACK_SIZE, || {
+            shen_fill_function_table();
+            for (token, spans) in parse_kl_with_spans(ERRORS_KL.as_bytes(), Some("tests/fixtures/errors.kl")).unwrap() {
+                eval_spanned(&token, Some(&Rc::new(spans)), &Env::new()).unwrap();
+            }
+            format!("{}", eval(&parse_kl(b"(errors.outer 1)").unwrap()[0], &Env::new()).unwrap_err())
+        }).unwrap().join().unwrap();
+        assert_eq!(evaluated, expected);
+        let compiled = shen_spawn_with_stack(SMALL_STACK_SIZE, || {
+            shen_fill_function_table();
+            errors::init();
+            let argument = Rc::new(KlElement::Number(KlNumber::Int(1)));
+            match shen_apply_arguments_to_function(KlSymbol::intern("errors.outer"), vec![argument]).map(shen_run_trampoline) {
+                Ok(KlClosure::Done(Err(e))) => format!("{}", e),
+                other => format!("unexpected {:?}", other)
+            }
+        }).unwrap().join().unwrap();
+        assert_eq!(compiled, expected);
+    }
+
+    #[test]
+    fn traces_place_an_error_from_a_lambda_at_the_call_of_the_lambda() {
+        let kl = "(defun mapper (F X) (+ 1 (F X)))\n(defun bad (X) (+ 1 (simple-error \"boom\")))\n(mapper (lambda X (+ 1 (bad X))) 1)";
+        let traced = shen_spawn_with_stack(SMALL_STACK_SIZE, move || {
+            shen_fill_function_table();
+            let mut last = Ok(shen_nil());
+            for (token, spans) in parse_kl_with_spans(kl.as_bytes(), Some("t.kl")).unwrap() {
+                last = eval_spanned(&token, Some(&Rc::new(spans)), &Env::new());
+            }
+            format!("{}", last.unwrap_err())
+        }).unwrap().join().unwrap();
+        assert_eq!(traced, "boom\n    in bad (t.kl:2:21)\n    in mapper (t.kl:1:26)");
+    }
+
+    fn evaluated(function: &str, n: i64) -> String {
+        let function = String::from(function);
+        shen_spawn_with_stack(SMALL_STACK_SIZE, move || eval_kl(&format!("{} ({} {})", TAIL_CALLS_KL, function, n)))
+            .unwrap().join().unwrap()
+    }
+
+    fn compiled(function: &str, n: i64) -> String {
+        let function = String::from(function);
+        shen_spawn_with_stack(SMALL_STACK_SIZE, move || {
+            shen_fill_function_table();
+            tail_calls::init();
+            let argument = Rc::new(KlElement::Number(KlNumber::Int(n)));
+            match shen_apply_arguments_to_function(KlSymbol::intern(&function), vec![argument]).map(shen_run_trampoline) {
+                Ok(KlClosure::Done(Ok(Some(value)))) => format!("{}", value),
+                Ok(KlClosure::Done(Err(e))) => format!("error: {}", e.message()),
+                Ok(other) => format!("unexpected {:?}", other),
+                Err(e) => format!("error: {}", e)
+            }
+        }).unwrap().join().unwrap()
+    }
+
+    #[test]
+    fn calls_in_tail_contexts_run_in_constant_stack() {
+        for context in &["if", "do", "let", "cond", "trap"] {
+            let function = format!("tc.{}-ping", context);
+            assert_eq!((&function, evaluated(&function, LOOP_LENGTH)), (&function, String::from("ping")));
+            assert_eq!((&function, compiled(&function, LOOP_LENGTH + 1)), (&function, String::from("pong")));
+        }
+    }
+
+    #[test]
+    fn calls_outside_tail_contexts_still_run_out_of_stack() {
+        let overflow = String::from("error: stack depth exceeded.");
+        assert_eq!(evaluated("tc.count", 100), "100");
+        assert_eq!(compiled("tc.count", 100), "100");
+        assert_eq!(evaluated("tc.count", LOOP_LENGTH), overflow);
+        assert_eq!(compiled("tc.count", LOOP_LENGTH), overflow);
+        assert_eq!(evaluated("tc.and-or-ping", 101), "true");
+        assert_eq!(compiled("tc.and-or-ping", 101), "true");
+        assert_eq!(evaluated("tc.and-or-ping", LOOP_LENGTH), overflow);
+        assert_eq!(compiled("tc.and-or-ping", LOOP_LENGTH), overflow);
+    }
+
+    #[test]
+    fn a_self_call_given_a_failed_argument_fails() {
+        assert_eq!(evaluated("tc.until-error", 3), "error: done");
+        assert_eq!(compiled("tc.until-error", 3), "error: done");
+    }
+
+    #[test]
+    fn and_or_check_both_operands_are_booleans() {
+        assert_eq!(eval_kl("(and true 5)"), "error: and: expecting 'true' or 'false'.");
+        assert_eq!(eval_kl("(or false 5)"), "error: or: expecting 'true' or 'false'.");
+        assert_eq!(eval_kl("(trap-error (and true 5) (lambda E (shen-rust.error-kind E)))"), "type");
+        let thunk = | value : Rc<KlElement> | Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new(move || value.clone()))));
+        let five = Rc::new(KlElement::Number(KlNumber::Int(5)));
+        for &(function, first) in [(KlSymbol::AND, true), (KlSymbol::OR, false)].iter() {
+            let applied = shen_apply_arguments_to_function(function, vec![thunk(shen_bool_to_symbol(first)), thunk(five.clone())]);
+            match applied.map(shen_run_trampoline) {
+                Ok(KlClosure::Done(Err(e))) => assert_eq!(e.message(), format!("{}: The second argument must evaluate to the symbol 'true' or 'false.", shen_symbol_name(function))),
+                other => panic!("{:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn only_the_documented_positions_are_tail_contexts() {
+        let contexts = | kl : &str | tail_contexts(&parse_kl(kl.as_bytes()).unwrap()[0]);
+        assert_eq!(contexts("(defun f (X) (g X))"), vec![vec![3]]);
+        assert_eq!(contexts("(if X (g X) (h X))"), vec![vec![2], vec![3]]);
+        assert_eq!(contexts("(do (g X) (h X) (i X))"), vec![vec![3]]);
+        assert_eq!(contexts("(let Y X (let Z Y (g Z)))"), vec![vec![3]]);
+        assert_eq!(contexts("(cond (X (g X)) (true (h X)))"), vec![vec![1, 1], vec![2, 1]]);
+        assert_eq!(contexts("(trap-error (g X) (lambda E (h E)))"), vec![vec![2, 2]]);
+        assert_eq!(contexts("(and X (g X))"), Vec::<Vec<usize>>::new());
+        assert_eq!(contexts("(or X (g X))"), Vec::<Vec<usize>>::new());
+        assert_eq!(contexts("(lambda X (g X))"), Vec::<Vec<usize>>::new());
+        assert_eq!(contexts("(freeze (g X))"), Vec::<Vec<usize>>::new());
+    }
+}
+// Tests:1 ends here
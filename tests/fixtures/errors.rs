@@ -0,0 +1,59 @@
+#![allow(unused_variables, unused_mut, non_snake_case)]
+use std::rc::Rc;
+use shen_rust::*;
+
+pub fn init() {
+let symbol___Plus__ = KlSymbol::new("__Plus__");
+let symbol_errors__Dot__inner = KlSymbol::new("errors__Dot__inner");
+let symbol_errors__Dot__outer = KlSymbol::new("errors__Dot__outer");
+let symbol_simple__Dash__error = KlSymbol::new("simple__Dash__error");
+// tests/fixtures/errors.kl:5
+shen_record_defun_span(symbol_errors__Dot__outer, KlSpan::new(Some("tests/fixtures/errors.kl"), 5, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_X| {
+let kl_X_Copy = (*kl_X).clone();
+{ let tail_function = symbol___Plus__;
+let tail_arguments = vec![
+Rc::new(KlElement::Number(KlNumber::Int(1))),match shen_apply_arguments_to_function(symbol_errors__Dot__inner, vec![
+Rc::new(kl_X_Copy.clone())
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/errors.kl"), 7, 6), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/errors.kl"), 6, 3))) } }
+})) }
+;
+add_to_function_table(symbol_errors__Dot__outer, temp.clone())
+};
+// tests/fixtures/errors.kl:9
+shen_record_defun_span(symbol_errors__Dot__inner, KlSpan::new(Some("tests/fixtures/errors.kl"), 9, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_X| {
+let kl_X_Copy = (*kl_X).clone();
+{ let do_value = 
+Rc::new(kl_X_Copy.clone())
+;
+match &*do_value {
+&KlElement::Closure(KlClosure::Done(Err(ref e))) => KlClosure::Done(Err(e.clone())),
+_ => 
+{ let tail_function = symbol___Plus__;
+let tail_arguments = vec![
+Rc::new(KlElement::Number(KlNumber::Int(1))),match shen_apply_arguments_to_function(symbol_simple__Dash__error, vec![
+Rc::new(KlElement::String(String::from("boom")))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/errors.kl"), 12, 10), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/errors.kl"), 11, 7))) } }
+} }
+})) }
+;
+add_to_function_table(symbol_errors__Dot__inner, temp.clone())
+};
+}
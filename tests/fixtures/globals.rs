@@ -0,0 +1,66 @@
+#![allow(unused_variables, unused_mut, non_snake_case)]
+use std::rc::Rc;
+use shen_rust::*;
+
+pub fn init() {
+let symbol___Equal__ = KlSymbol::new("__Equal__");
+let symbol___Star__language__Star__ = KlSymbol::new("__Star__language__Star__");
+let symbol___Star__stoutput__Star__ = KlSymbol::new("__Star__stoutput__Star__");
+let symbol_ok = KlSymbol::new("ok");
+let symbol_shen__Dot__shen = KlSymbol::new("shen__Dot__shen");
+let symbol_shen_if = KlSymbol::new("shen_if");
+let symbol_simple__Dash__error = KlSymbol::new("simple__Dash__error");
+let symbol_value = KlSymbol::new("value");
+// tests/fixtures/globals.kl:5
+shen_record_defun_span(symbol_shen__Dot__shen, KlSpan::new(Some("tests/fixtures/globals.kl"), 5, 1));
+{
+let temp = 
+KlClosure::Thunk(Rc::new(move || shen_tail_closure_to_element(
+{ let do_value = 
+match shen_apply_arguments_to_function(symbol_value, vec![
+Rc::new(KlElement::Symbol(symbol___Star__stoutput__Star__))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/globals.kl"), 6, 7), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+;
+match &*do_value {
+&KlElement::Closure(KlClosure::Done(Err(ref e))) => KlClosure::Done(Err(e.clone())),
+_ => 
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+match shen_apply_arguments_to_function(symbol_value, vec![
+Rc::new(KlElement::Symbol(symbol___Star__language__Star__))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/globals.kl"), 7, 14), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::String(String::from("Rust")))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/globals.kl"), 7, 11), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( {  move|| { 
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ok))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( {  move|| { 
+shen_tail_closure_to_element({
+{ let tail_function = symbol_simple__Dash__error;
+let tail_arguments = vec![
+Rc::new(KlElement::String(String::from("*language* is not set")))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/globals.kl"), 9, 11))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/globals.kl"), 7, 7), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+} }
+)))
+;
+add_to_function_table(symbol_shen__Dot__shen, temp.clone())
+};
+}
@@ -0,0 +1,915 @@
+#![allow(unused_variables, unused_mut, non_snake_case)]
+use std::rc::Rc;
+use shen_rust::*;
+
+pub fn init() {
+let symbol___Dash__ = KlSymbol::new("__Dash__");
+let symbol___Equal__ = KlSymbol::new("__Equal__");
+let symbol___LT__ = KlSymbol::new("__LT__");
+let symbol___Plus__ = KlSymbol::new("__Plus__");
+let symbol_and = KlSymbol::new("and");
+let symbol_cond = KlSymbol::new("cond");
+let symbol_never = KlSymbol::new("never");
+let symbol_or = KlSymbol::new("or");
+let symbol_ping = KlSymbol::new("ping");
+let symbol_pong = KlSymbol::new("pong");
+let symbol_shen_if = KlSymbol::new("shen_if");
+let symbol_shen_true = KlSymbol::new("shen_true");
+let symbol_simple__Dash__error = KlSymbol::new("simple__Dash__error");
+let symbol_tc__Dot__and__Dash__or__Dash__ping = KlSymbol::new("tc__Dot__and__Dash__or__Dash__ping");
+let symbol_tc__Dot__and__Dash__or__Dash__pong = KlSymbol::new("tc__Dot__and__Dash__or__Dash__pong");
+let symbol_tc__Dot__cond__Dash__ping = KlSymbol::new("tc__Dot__cond__Dash__ping");
+let symbol_tc__Dot__cond__Dash__pong = KlSymbol::new("tc__Dot__cond__Dash__pong");
+let symbol_tc__Dot__count = KlSymbol::new("tc__Dot__count");
+let symbol_tc__Dot__do__Dash__ping = KlSymbol::new("tc__Dot__do__Dash__ping");
+let symbol_tc__Dot__do__Dash__pong = KlSymbol::new("tc__Dot__do__Dash__pong");
+let symbol_tc__Dot__if__Dash__ping = KlSymbol::new("tc__Dot__if__Dash__ping");
+let symbol_tc__Dot__if__Dash__pong = KlSymbol::new("tc__Dot__if__Dash__pong");
+let symbol_tc__Dot__let__Dash__ping = KlSymbol::new("tc__Dot__let__Dash__ping");
+let symbol_tc__Dot__let__Dash__pong = KlSymbol::new("tc__Dot__let__Dash__pong");
+let symbol_tc__Dot__next__Dash__or__Dash__error = KlSymbol::new("tc__Dot__next__Dash__or__Dash__error");
+let symbol_tc__Dot__trap__Dash__ping = KlSymbol::new("tc__Dot__trap__Dash__ping");
+let symbol_tc__Dot__trap__Dash__pong = KlSymbol::new("tc__Dot__trap__Dash__pong");
+let symbol_tc__Dot__until__Dash__error = KlSymbol::new("tc__Dot__until__Dash__error");
+let symbol_trap__Dash__error = KlSymbol::new("trap__Dash__error");
+// tests/fixtures/tail_calls.kl:6
+shen_record_defun_span(symbol_tc__Dot__if__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 6, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 6, 27), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ping))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__if__Dash__pong;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 6, 52), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 6, 40))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 6, 23), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__if__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:8
+shen_record_defun_span(symbol_tc__Dot__if__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 8, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 8, 27), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_pong))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__if__Dash__ping;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 8, 52), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 8, 40))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 8, 23), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__if__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:10
+shen_record_defun_span(symbol_tc__Dot__do__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 10, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 10, 27), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ping))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let do_value = 
+match shen_apply_arguments_to_function(symbol___Plus__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 10, 44), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+;
+match &*do_value {
+&KlElement::Closure(KlClosure::Done(Err(ref e))) => KlClosure::Done(Err(e.clone())),
+_ => 
+{ let tail_function = symbol_tc__Dot__do__Dash__pong;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 10, 64), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 10, 52))) } }
+} }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 10, 23), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__do__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:12
+shen_record_defun_span(symbol_tc__Dot__do__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 12, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 12, 27), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_pong))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let do_value = 
+match shen_apply_arguments_to_function(symbol___Plus__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 12, 44), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+;
+match &*do_value {
+&KlElement::Closure(KlClosure::Done(Err(ref e))) => KlClosure::Done(Err(e.clone())),
+_ => 
+{ let tail_function = symbol_tc__Dot__do__Dash__ping;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 12, 64), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 12, 52))) } }
+} }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 12, 23), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__do__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:14
+shen_record_defun_span(symbol_tc__Dot__let__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 14, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 14, 28), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ping))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_lambda({ let kl_N = kl_N.clone(); KlClosure::FeedMe(Rc::new(move |kl_M| {
+let kl_M_Copy = (*kl_M).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+match shen_apply_arguments_to_lambda({ let kl_N = kl_N.clone();let kl_M = kl_M.clone(); KlClosure::FeedMe(Rc::new(move |kl_K| {
+let kl_K_Copy = (*kl_K).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+let kl_M_Copy = (*kl_M).clone();
+let kl_M = kl_M.clone();
+{ let tail_function = symbol_tc__Dot__let__Dash__pong;
+let tail_arguments = vec![
+Rc::new(kl_K_Copy.clone())
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 14, 65))) } }
+})) }, Rc::new(kl_M_Copy.clone())) {
+Ok(c) => c.clone(), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }, match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 14, 48), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}) {
+Ok(c) => c.clone(), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 14, 24), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__let__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:16
+shen_record_defun_span(symbol_tc__Dot__let__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 16, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 16, 28), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_pong))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_lambda({ let kl_N = kl_N.clone(); KlClosure::FeedMe(Rc::new(move |kl_M| {
+let kl_M_Copy = (*kl_M).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+match shen_apply_arguments_to_lambda({ let kl_N = kl_N.clone();let kl_M = kl_M.clone(); KlClosure::FeedMe(Rc::new(move |kl_K| {
+let kl_K_Copy = (*kl_K).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+let kl_M_Copy = (*kl_M).clone();
+let kl_M = kl_M.clone();
+{ let tail_function = symbol_tc__Dot__let__Dash__ping;
+let tail_arguments = vec![
+Rc::new(kl_K_Copy.clone())
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 16, 65))) } }
+})) }, Rc::new(kl_M_Copy.clone())) {
+Ok(c) => c.clone(), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }, match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 16, 48), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}) {
+Ok(c) => c.clone(), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 16, 24), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__let__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:18
+shen_record_defun_span(symbol_tc__Dot__cond__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 18, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_cond, vec![
+shen_list(vec![
+shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Equal__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 18, 32))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ping))
+)))
+})
+ }}))))]),shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___LT__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 18, 47))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_never))
+)))
+})
+ }}))))]),shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_shen_true))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__cond__Dash__pong;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 18, 82), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 18, 68))) } }
+})
+ }}))))])
+])
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 18, 25), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__cond__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:20
+shen_record_defun_span(symbol_tc__Dot__cond__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 20, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_cond, vec![
+shen_list(vec![
+shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Equal__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 20, 32))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_pong))
+)))
+})
+ }}))))]),shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___LT__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 20, 47))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_never))
+)))
+})
+ }}))))]),shen_list(vec![Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_shen_true))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__cond__Dash__ping;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 20, 82), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 20, 68))) } }
+})
+ }}))))])
+])
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 20, 25), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__cond__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:22
+shen_record_defun_span(symbol_tc__Dot__trap__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 22, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 22, 29), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_ping))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_function(symbol_trap__Dash__error, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_simple__Dash__error;
+let tail_arguments = vec![
+Rc::new(KlElement::String(String::from("next")))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 22, 54))) } }
+})
+ }})))),Rc::new(KlElement::Closure(
+
+{ let kl_N = kl_N.clone(); KlClosure::FeedMe(Rc::new(move |kl_E| {
+let kl_E_Copy = (*kl_E).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+{ let tail_function = symbol_tc__Dot__trap__Dash__pong;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 22, 100), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 22, 86))) } }
+})) }
+))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 22, 42), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 22, 25), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__trap__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:24
+shen_record_defun_span(symbol_tc__Dot__trap__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 24, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 24, 29), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_pong))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_function(symbol_trap__Dash__error, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_simple__Dash__error;
+let tail_arguments = vec![
+Rc::new(KlElement::String(String::from("next")))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 24, 54))) } }
+})
+ }})))),Rc::new(KlElement::Closure(
+
+{ let kl_N = kl_N.clone(); KlClosure::FeedMe(Rc::new(move |kl_E| {
+let kl_E_Copy = (*kl_E).clone();
+let kl_N_Copy = (*kl_N).clone();
+let kl_N = kl_N.clone();
+{ let tail_function = symbol_tc__Dot__trap__Dash__ping;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 24, 100), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 24, 86))) } }
+})) }
+))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 24, 42), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 24, 25), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__trap__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:28
+shen_record_defun_span(symbol_tc__Dot__and__Dash__or__Dash__ping, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 28, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_or, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Equal__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 28, 31))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_function(symbol_and, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_shen_true))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__and__Dash__or__Dash__pong;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 28, 65), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 28, 49))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 28, 39), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 28, 27), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__and__Dash__or__Dash__ping, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:30
+shen_record_defun_span(symbol_tc__Dot__and__Dash__or__Dash__pong, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 30, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_or, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Equal__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 30, 31))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+match shen_apply_arguments_to_function(symbol_and, vec![
+Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Symbol(symbol_shen_true))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_tc__Dot__and__Dash__or__Dash__ping;
+let tail_arguments = vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 30, 65), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 30, 49))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 30, 39), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 30, 27), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__and__Dash__or__Dash__pong, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:34
+shen_record_defun_span(symbol_tc__Dot__until__Dash__error, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 34, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+{
+let trampoline = | 
+kl_N : Rc<KlElement>
+| {
+let kl_N_Copy : KlElement = (*kl_N).clone();
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Recur(vec![
+match shen_apply_arguments_to_function(symbol_tc__Dot__next__Dash__or__Dash__error, vec![
+Rc::new(kl_N_Copy.clone())
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 34, 43), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+]))
+)))
+};
+let mut done= None;
+let mut current_args = vec![kl_N.clone()];
+while !done.is_some() {
+let result = trampoline(current_args[0].clone());
+let recur = match &result { &KlClosure::Done(Ok(Some(ref v))) => match &**v { &KlElement::Recur(ref v) => Some(v.clone()), _ => None }, _ => None };
+match recur { Some(v) => match shen_failed_argument(&v) { Some(e) => done = Some(KlClosure::Done(Err(e))), None => current_args = v }, None => done = Some(result.clone()) };
+}
+done.unwrap()
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__until__Dash__error, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:36
+shen_record_defun_span(symbol_tc__Dot__next__Dash__or__Dash__error, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 36, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 36, 33), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol_simple__Dash__error;
+let tail_arguments = vec![
+Rc::new(KlElement::String(String::from("done")))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 36, 41))) } }
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Dash__;
+let tail_arguments = vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 36, 63))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 36, 29), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__next__Dash__or__Dash__error, temp.clone())
+};
+// tests/fixtures/tail_calls.kl:38
+shen_record_defun_span(symbol_tc__Dot__count, KlSpan::new(Some("tests/fixtures/tail_calls.kl"), 38, 1));
+{
+let temp = 
+{  KlClosure::FeedMe(Rc::new(move |kl_N| {
+let kl_N_Copy = (*kl_N).clone();
+match shen_apply_arguments_to_function(symbol_shen_if, vec![
+match shen_apply_arguments_to_function(symbol___Equal__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(0)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 38, 25), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+},Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+KlClosure::Done(Ok(Some(
+Rc::new(KlElement::Number(KlNumber::Int(0)))
+)))
+})
+ }})))),Rc::new(KlElement::Closure(KlClosure::Thunk(Rc::new( { let kl_N_Copy = kl_N_Copy.clone(); move|| { 
+let kl_N = Rc::new(kl_N_Copy.clone());
+let kl_N_Copy = (*kl_N).clone();
+shen_tail_closure_to_element({
+{ let tail_function = symbol___Plus__;
+let tail_arguments = vec![
+Rc::new(KlElement::Number(KlNumber::Int(1))),match shen_apply_arguments_to_function(symbol_tc__Dot__count, vec![
+match shen_apply_arguments_to_function(symbol___Dash__, vec![
+Rc::new(kl_N_Copy.clone()),Rc::new(KlElement::Number(KlNumber::Int(1)))
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 38, 50), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+]) {
+Ok(c) => shen_element_at(shen_closure_to_element(c.clone()), Some("tests/fixtures/tail_calls.kl"), 38, 40), 
+ Err(s) => Rc::new(KlElement::Closure(KlClosure::Done(Err(shen_application_error(s.as_str())))))
+}
+];
+match shen_failed_argument(&tail_arguments) { Some(e) => KlClosure::Done(Err(e)), None =>
+shen_tail_call(move || shen_apply_arguments_to_function(tail_function, tail_arguments.clone()).map(| c | shen_closure_at(c, Some("tests/fixtures/tail_calls.kl"), 38, 35))) } }
+})
+ }}))))
+]) {
+Ok(c) => shen_closure_at(c.clone(), Some("tests/fixtures/tail_calls.kl"), 38, 21), 
+ Err(s) => KlClosure::Done(Err(shen_application_error(s.as_str())))
+}
+})) }
+;
+add_to_function_table(symbol_tc__Dot__count, temp.clone())
+};
+}
@@ -0,0 +1,33 @@
+// [[file:../shen-rust.org::*Tests][Tests:2]]
+use std::io::prelude::*;
+use std::process::{Command, Stdio};
+
+// Boots the kernel in a repl, types input at it and gives back what the
+// repl printed once stdin ran out.
+fn repl(input: &str) -> String {
+    let mut repl = Command::new(env!("CARGO_BIN_EXE_shen-rust"))
+        .arg("--kernel-dir").arg(concat!(env!("CARGO_MANIFEST_DIR"), "/KLambda/")).arg("repl")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    repl.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = repl.wait_with_output().unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn the_repl_prints_the_trace_of_an_error_after_its_message() {
+    let printed = repl("(define g X -> (simple-error \"deep\"))\n(g 1)\n(+ 1 2)\n");
+    assert!(printed.contains("deep\n    in g\n"), "{}", printed);
+    assert!(printed.contains(" 3\n"), "{}", printed);
+}
+
+#[test]
+fn the_repl_prints_a_dotted_pair_with_a_bar() {
+    let printed = repl("(cons 1 2)\n(hd (cons 1 2))\n");
+    assert!(printed.contains(" [1 | 2]\n"), "{}", printed);
+    assert!(printed.contains(" 1\n"), "{}", printed);
+}
+// Tests:2 ends here